@@ -0,0 +1,493 @@
+//! Structured values of [PackageDependencies](crate::PackageDependencies), see [npm-package-arg](https://github.com/npm/npm-package-arg).
+
+use crate::error::{Error, Result};
+use crate::Range;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A dependency specifier, the value of a [PackageDependencies](crate::PackageDependencies) entry.
+///
+/// Every specifier is written back to the exact string it was parsed from.
+/// ```
+/// use package_json::{DependencySpec, GitCommittish};
+///
+/// let spec = DependencySpec::parse("git+ssh://git@github.com/npm/cli.git#semver:^9").unwrap();
+/// assert_eq!(
+///   spec,
+///   DependencySpec::Git {
+///     url: "git+ssh://git@github.com/npm/cli.git".to_string(),
+///     committish: Some(GitCommittish::Semver("^9".to_string())),
+///   }
+/// );
+/// assert_eq!(spec.to_string(), "git+ssh://git@github.com/npm/cli.git#semver:^9");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencySpec {
+  /// A semver version or range, e.g. `^1.2.3`, `1.x` or `*`.
+  Range(String),
+  /// A dist-tag, e.g. `latest` or `next`.
+  Tag(String),
+  /// `npm:<name>` or `npm:<name>@<spec>`, which installs the package `name` under another name.
+  Alias {
+    name: String,
+    /// A [Range][DependencySpec::Range] or a [Tag][DependencySpec::Tag].
+    spec: Option<Box<DependencySpec>>,
+  },
+  /// A git URL, e.g. `git+ssh://git@github.com:npm/cli.git#v1.0.27` or `git@github.com:npm/cli.git`.
+  Git {
+    url: String,
+    committish: Option<GitCommittish>,
+  },
+  /// A hosted git shorthand, e.g. `github:user/repo#ref`, `gitlab:user/repo`, `gist:id` or `user/repo`.
+  Hosted {
+    /// `None` for the `user/repo` form, which is a GitHub repository.
+    host: Option<GitHost>,
+    /// The owner of the repository, which is optional for a gist.
+    user: Option<String>,
+    /// The repository name, or the id of a gist.
+    project: String,
+    committish: Option<GitCommittish>,
+  },
+  /// A local directory or tarball, e.g. `file:../pkg`, `../pkg` or `./pkg.tgz`.
+  File {
+    path: String,
+    /// Whether the path is prefixed with the `file:` protocol.
+    prefixed: bool,
+  },
+  /// `link:<path>`, a symlink to a local directory.
+  Link(String),
+  /// `workspace:<range>`, a package of the current workspace, e.g. `workspace:^`, `workspace:*` or `workspace:^1.2.0`.
+  Workspace(String),
+  /// `catalog:<name>`, a range defined in a catalog of the workspace, the name is empty for the default catalog.
+  Catalog(String),
+  /// A remote tarball URL, e.g. `https://example.com/pkg.tgz`.
+  Tarball(String),
+}
+
+/// The hosts of [DependencySpec::Hosted].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GitHost {
+  GitHub,
+  GitLab,
+  Bitbucket,
+  Gist,
+}
+
+/// The fragment of a git specifier, which selects the commit to install.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitCommittish {
+  /// A branch, tag or commit hash, e.g. `#v1.0.27`.
+  Ref(String),
+  /// `#semver:<range>`, the highest tag which satisfies the range.
+  Semver(String),
+}
+
+impl GitHost {
+  /// The shorthand protocol, e.g. `github` of `github:user/repo`.
+  pub fn shortcut(&self) -> &'static str {
+    match self {
+      Self::GitHub => "github",
+      Self::GitLab => "gitlab",
+      Self::Bitbucket => "bitbucket",
+      Self::Gist => "gist",
+    }
+  }
+
+  fn from_shortcut(shortcut: &str) -> Option<Self> {
+    [Self::GitHub, Self::GitLab, Self::Bitbucket, Self::Gist]
+      .into_iter()
+      .find(|host| host.shortcut() == shortcut)
+  }
+}
+
+impl DependencySpec {
+  /// Parse a dependency specifier.
+  pub fn parse(spec: &str) -> Result<Self> {
+    let error = || Error::Validation(format!("Invalid dependency specifier \"{}\".", spec));
+
+    if let Some(alias) = spec.strip_prefix("npm:") {
+      let (name, aliased) = match alias.char_indices().skip(1).find(|(_, char)| *char == '@') {
+        Some((index, _)) => (&alias[..index], Some(&alias[index + 1..])),
+        None => (alias, None),
+      };
+      let aliased = match aliased.map(Self::parse).transpose()? {
+        Some(aliased @ (Self::Range(_) | Self::Tag(_))) => Some(Box::new(aliased)),
+        Some(_) => return Err(error()),
+        None => None,
+      };
+      if name.is_empty() {
+        return Err(error());
+      }
+      return Ok(Self::Alias {
+        name: name.to_owned(),
+        spec: aliased,
+      });
+    }
+    if let Some(range) = spec.strip_prefix("workspace:") {
+      return Ok(Self::Workspace(range.to_owned()));
+    }
+    if let Some(name) = spec.strip_prefix("catalog:") {
+      return Ok(Self::Catalog(name.to_owned()));
+    }
+    if let Some(path) = spec.strip_prefix("file:") {
+      return Ok(Self::File {
+        path: path.to_owned(),
+        prefixed: true,
+      });
+    }
+    if let Some(path) = spec.strip_prefix("link:") {
+      return Ok(Self::Link(path.to_owned()));
+    }
+    if let Some((shortcut, repository)) = spec.split_once(':') {
+      if let Some(host) = GitHost::from_shortcut(shortcut) {
+        return Self::parse_hosted(Some(host), repository).ok_or_else(error);
+      }
+    }
+
+    let (url, fragment) = match spec.split_once('#') {
+      Some((url, fragment)) => (url, Some(fragment)),
+      None => (spec, None),
+    };
+    let is_git = url.starts_with("git+")
+      || url.starts_with("git://")
+      || (url.starts_with("http") && url.ends_with(".git"))
+      || is_scp_like(url);
+    if is_git {
+      return Ok(Self::Git {
+        url: url.to_owned(),
+        committish: fragment.map(GitCommittish::parse),
+      });
+    }
+    if spec.starts_with("http://") || spec.starts_with("https://") {
+      return Ok(Self::Tarball(spec.to_owned()));
+    }
+    if is_path(spec) {
+      return Ok(Self::File {
+        path: spec.to_owned(),
+        prefixed: false,
+      });
+    }
+    if spec.contains('/') {
+      return Self::parse_hosted(None, spec).ok_or_else(error);
+    }
+    if Range::parse(spec).is_ok() {
+      return Ok(Self::Range(spec.to_owned()));
+    }
+    if !spec.is_empty()
+      && spec
+        .chars()
+        .all(|char| char.is_ascii_alphanumeric() || matches!(char, '-' | '.' | '_' | '~'))
+    {
+      return Ok(Self::Tag(spec.to_owned()));
+    }
+    Err(error())
+  }
+
+  fn parse_hosted(host: Option<GitHost>, repository: &str) -> Option<Self> {
+    let (repository, committish) = match repository.split_once('#') {
+      Some((repository, fragment)) => (repository, Some(GitCommittish::parse(fragment))),
+      None => (repository, None),
+    };
+    let is_segment = |segment: &str| {
+      !segment.is_empty()
+        && !segment.starts_with('.')
+        && segment
+          .chars()
+          .all(|char| char.is_ascii_alphanumeric() || matches!(char, '-' | '.' | '_'))
+    };
+    let (user, project) = match repository.split_once('/') {
+      Some((user, project)) => (Some(user), project),
+      None if host == Some(GitHost::Gist) => (None, repository),
+      None => return None,
+    };
+    if !user.is_none_or(is_segment) || !is_segment(project) {
+      return None;
+    }
+    Some(Self::Hosted {
+      host,
+      user: user.map(|user| user.to_owned()),
+      project: project.to_owned(),
+      committish,
+    })
+  }
+
+  /// The semver range of a registry dependency, an aliased registry dependency or a workspace dependency with an explicit range.
+  /// ```
+  /// use package_json::DependencySpec;
+  ///
+  /// let spec = DependencySpec::parse("npm:@scope/pkg@^1.2.0").unwrap();
+  /// assert!(spec.range().is_some());
+  /// assert!(DependencySpec::parse("latest").unwrap().range().is_none());
+  /// ```
+  pub fn range(&self) -> Option<Range> {
+    match self {
+      Self::Range(range) => Range::parse(range).ok(),
+      Self::Alias {
+        spec: Some(spec), ..
+      } => spec.range(),
+      Self::Workspace(range) if !matches!(range.as_str(), "" | "*" | "^" | "~") => {
+        Range::parse(range).ok()
+      }
+      _ => None,
+    }
+  }
+}
+
+/// Whether `url` is a scp-like git url, e.g. `git@github.com:npm/cli.git`.
+fn is_scp_like(url: &str) -> bool {
+  match (url.find('@'), url.find(':')) {
+    (Some(at), Some(colon)) => {
+      at > 0 && at < colon && !url.contains("://") && !url[..at].contains('/')
+    }
+    _ => false,
+  }
+}
+
+/// Whether `spec` is a local path, e.g. `./pkg`, `~/pkg`, `/pkg`, `C:\pkg` or `pkg.tgz`.
+fn is_path(spec: &str) -> bool {
+  spec.starts_with('.')
+    || spec.starts_with('/')
+    || spec.starts_with("~/")
+    || spec.starts_with('\\')
+    || (spec.len() > 2 && spec.as_bytes()[0].is_ascii_alphabetic() && spec[1..].starts_with(":\\"))
+    || [".tgz", ".tar.gz", ".tar"]
+      .iter()
+      .any(|extension| spec.ends_with(extension))
+}
+
+impl GitCommittish {
+  fn parse(fragment: &str) -> Self {
+    match fragment.strip_prefix("semver:") {
+      Some(range) => Self::Semver(range.to_owned()),
+      None => Self::Ref(fragment.to_owned()),
+    }
+  }
+}
+
+impl fmt::Display for GitCommittish {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Ref(reference) => write!(f, "#{}", reference),
+      Self::Semver(range) => write!(f, "#semver:{}", range),
+    }
+  }
+}
+
+impl fmt::Display for DependencySpec {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Range(range) => f.write_str(range),
+      Self::Tag(tag) => f.write_str(tag),
+      Self::Alias { name, spec } => {
+        write!(f, "npm:{}", name)?;
+        match spec {
+          Some(spec) => write!(f, "@{}", spec),
+          None => Ok(()),
+        }
+      }
+      Self::Git { url, committish } => {
+        f.write_str(url)?;
+        match committish {
+          Some(committish) => write!(f, "{}", committish),
+          None => Ok(()),
+        }
+      }
+      Self::Hosted {
+        host,
+        user,
+        project,
+        committish,
+      } => {
+        if let Some(host) = host {
+          write!(f, "{}:", host.shortcut())?;
+        }
+        if let Some(user) = user {
+          write!(f, "{}/", user)?;
+        }
+        f.write_str(project)?;
+        match committish {
+          Some(committish) => write!(f, "{}", committish),
+          None => Ok(()),
+        }
+      }
+      Self::File { path, prefixed } => {
+        if *prefixed {
+          f.write_str("file:")?;
+        }
+        f.write_str(path)
+      }
+      Self::Link(path) => write!(f, "link:{}", path),
+      Self::Workspace(range) => write!(f, "workspace:{}", range),
+      Self::Catalog(name) => write!(f, "catalog:{}", name),
+      Self::Tarball(url) => f.write_str(url),
+    }
+  }
+}
+
+impl FromStr for DependencySpec {
+  type Err = Error;
+
+  fn from_str(spec: &str) -> Result<Self> {
+    Self::parse(spec)
+  }
+}
+
+impl Serialize for DependencySpec {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for DependencySpec {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let spec = String::deserialize(deserializer)?;
+    Self::parse(&spec).map_err(serde::de::Error::custom)
+  }
+}
+
+#[test]
+fn test_parse() {
+  let range = |range: &str| DependencySpec::Range(range.to_owned());
+  let reference = |reference: &str| Some(GitCommittish::Ref(reference.to_owned()));
+  for (spec, expected) in [
+    ("^1.2.3", range("^1.2.3")),
+    (">=1.0.0 <2", range(">=1.0.0 <2")),
+    ("*", range("*")),
+    ("", range("")),
+    ("latest", DependencySpec::Tag("latest".to_owned())),
+    (
+      "npm:@scope/pkg@^1",
+      DependencySpec::Alias {
+        name: "@scope/pkg".to_owned(),
+        spec: Some(Box::new(range("^1"))),
+      },
+    ),
+    (
+      "npm:pkg",
+      DependencySpec::Alias {
+        name: "pkg".to_owned(),
+        spec: None,
+      },
+    ),
+    (
+      "git+ssh://git@github.com/npm/cli.git#semver:^2",
+      DependencySpec::Git {
+        url: "git+ssh://git@github.com/npm/cli.git".to_owned(),
+        committish: Some(GitCommittish::Semver("^2".to_owned())),
+      },
+    ),
+    (
+      "git://github.com/npm/cli.git#v1.0.27",
+      DependencySpec::Git {
+        url: "git://github.com/npm/cli.git".to_owned(),
+        committish: reference("v1.0.27"),
+      },
+    ),
+    (
+      "git@github.com:npm/cli.git",
+      DependencySpec::Git {
+        url: "git@github.com:npm/cli.git".to_owned(),
+        committish: None,
+      },
+    ),
+    (
+      "https://github.com/npm/cli.git",
+      DependencySpec::Git {
+        url: "https://github.com/npm/cli.git".to_owned(),
+        committish: None,
+      },
+    ),
+    (
+      "github:user/repo#main",
+      DependencySpec::Hosted {
+        host: Some(GitHost::GitHub),
+        user: Some("user".to_owned()),
+        project: "repo".to_owned(),
+        committish: reference("main"),
+      },
+    ),
+    (
+      "gist:11081aaa281",
+      DependencySpec::Hosted {
+        host: Some(GitHost::Gist),
+        user: None,
+        project: "11081aaa281".to_owned(),
+        committish: None,
+      },
+    ),
+    (
+      "user/repo",
+      DependencySpec::Hosted {
+        host: None,
+        user: Some("user".to_owned()),
+        project: "repo".to_owned(),
+        committish: None,
+      },
+    ),
+    (
+      "file:../x",
+      DependencySpec::File {
+        path: "../x".to_owned(),
+        prefixed: true,
+      },
+    ),
+    (
+      "./pkg.tgz",
+      DependencySpec::File {
+        path: "./pkg.tgz".to_owned(),
+        prefixed: false,
+      },
+    ),
+    ("link:../y", DependencySpec::Link("../y".to_owned())),
+    ("workspace:^", DependencySpec::Workspace("^".to_owned())),
+    ("catalog:", DependencySpec::Catalog("".to_owned())),
+    (
+      "catalog:react18",
+      DependencySpec::Catalog("react18".to_owned()),
+    ),
+    (
+      "https://example.com/pkg-1.0.0.tgz",
+      DependencySpec::Tarball("https://example.com/pkg-1.0.0.tgz".to_owned()),
+    ),
+  ] {
+    let parsed = DependencySpec::parse(spec).unwrap();
+    assert_eq!(parsed, expected, "parse {}", spec);
+    assert_eq!(parsed.to_string(), spec);
+  }
+
+  for spec in [
+    "not a tag!",
+    "npm:",
+    "npm:pkg@git://x.git",
+    "github:user",
+    "a/b/c",
+  ] {
+    assert!(DependencySpec::parse(spec).is_err(), "parse {}", spec);
+  }
+}
+
+#[test]
+fn test_range() {
+  use crate::Version;
+
+  let version = Version::parse("1.2.3").unwrap();
+  for (spec, expected) in [
+    ("^1.0.0", Some(true)),
+    ("npm:pkg@~1.2.0", Some(true)),
+    ("workspace:^2.0.0", Some(false)),
+    ("workspace:^", None),
+    ("github:user/repo#semver:^1", None),
+    ("next", None),
+  ] {
+    assert_eq!(
+      DependencySpec::parse(spec)
+        .unwrap()
+        .range()
+        .map(|range| range.satisfies(&version)),
+      expected,
+      "range of {}",
+      spec
+    );
+  }
+}
@@ -0,0 +1,131 @@
+use crate::ParseError;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// A specialized `Result` type of this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The errors of this crate.
+/// ```
+/// use package_json::{Error, PackageJsonManager};
+///
+/// let mut manager = PackageJsonManager::new();
+/// match manager.read_ref() {
+///   Ok(json) => println!("{}@{}", json.name, json.version),
+///   Err(Error::NotLocated { .. }) => println!("Call `locate_closest` first."),
+///   Err(Error::Parse(error)) => println!("{}", error.code_frame()),
+///   Err(error) => println!("{}", error),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+  /// The file isn't located, either no file is found in any ancestor of `from`, or no file path is given to [PackageJsonManager](crate::PackageJsonManager).
+  NotLocated {
+    filename: String,
+    from: Option<PathBuf>,
+  },
+  /// Failed to read or write the file at `path`.
+  Io { path: PathBuf, source: io::Error },
+  /// The file isn't a valid `package.json`.
+  Parse(ParseError),
+  /// Failed to serialize the `package.json`.
+  Serialize(serde_json::Error),
+  /// A value doesn't meet its requirements, e.g. an invalid version, range, dependency specifier or license expression.
+  Validation(String),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::NotLocated {
+        filename,
+        from: Some(from),
+      } => write!(
+        f,
+        "Couldn't find an available \"{}\" from {}.",
+        filename,
+        from.display()
+      ),
+      Self::NotLocated {
+        filename,
+        from: None,
+      } => {
+        write!(f, "Couldn't find an available {} file.", filename)
+      }
+      Self::Io { path, source } => write!(f, "Couldn't access {}: {}.", path.display(), source),
+      Self::Parse(error) => write!(f, "{}", error),
+      Self::Serialize(error) => write!(f, "Couldn't serialize JSON: {}.", error),
+      Self::Validation(message) => f.write_str(message),
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Self::Io { source, .. } => Some(source),
+      Self::Serialize(error) => Some(error),
+      _ => None,
+    }
+  }
+}
+
+impl From<ParseError> for Error {
+  fn from(error: ParseError) -> Self {
+    Self::Parse(error)
+  }
+}
+
+#[test]
+fn test_error() {
+  use crate::PackageJsonManager;
+  use std::env::current_dir;
+  use tempfile::tempdir_in;
+
+  let mut manager = PackageJsonManager::new();
+  assert!(matches!(
+    manager.read_ref(),
+    Err(Error::NotLocated { from: None, .. })
+  ));
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  match manager.locate_closest_from(dir.path().join("a/b")) {
+    Err(Error::NotLocated {
+      from: Some(from), ..
+    }) => assert_eq!(from, dir.path().join("a/b")),
+    result => panic!("unexpected {:?}", result),
+  }
+
+  let file_path = dir.path().join("package.json");
+  manager.set_file_path(&file_path);
+  match manager.read_ref() {
+    Err(Error::Io { path, source }) => {
+      assert_eq!(path, file_path);
+      assert_eq!(source.kind(), io::ErrorKind::NotFound);
+    }
+    result => panic!("unexpected {:?}", result),
+  }
+
+  std::fs::write(&file_path, r#"{ "name": "test", "version": 1 }"#).unwrap();
+  match manager.read_ref() {
+    Err(Error::Parse(error)) => {
+      assert_eq!(error.pointer, "/version");
+      assert_eq!(error.file_path.as_deref(), Some(file_path.as_path()));
+    }
+    result => panic!("unexpected {:?}", result),
+  }
+
+  std::fs::write(&file_path, r#"{ "name": "test", "version": "x" }"#).unwrap();
+  let error = manager.bump(crate::ReleaseType::Patch).unwrap_err();
+  assert!(matches!(error, Error::Validation(_)));
+  assert_eq!(
+    error.to_string(),
+    "Invalid version \"x\": expected <major>.<minor>.<patch>."
+  );
+
+  assert!(matches!(
+    manager.write_to(&dir.path().join("missing/package.json")),
+    Err(Error::Io { .. })
+  ));
+}
@@ -1,43 +1,99 @@
-use anyhow::{format_err, Result};
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use serde::de::IgnoredAny;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use serde_json::{Map, Value};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
-use self::write_options::WriteOptions;
+use self::parse_error::ParseError;
+use self::write_options::{LineEnding, WriteOptions};
 
+pub(crate) mod document;
+pub(crate) mod parse_error;
 pub(crate) mod write_options;
 
 pub fn find_closest_file<P: AsRef<Path>>(filename: &str, current_dir: P) -> Result<PathBuf> {
-  let mut current_dir = PathBuf::from(current_dir.as_ref());
-  loop {
-    let file_path = current_dir.join(filename);
-    if file_path.exists() {
-      return Ok(file_path);
-    }
-    if !current_dir.pop() {
-      return Err(format_err!(
-        "Couldn't find an available \"{}\" from {}.",
-        filename,
-        current_dir.display()
-      ));
-    }
-  }
+  let from = current_dir.as_ref();
+  from
+    .ancestors()
+    .map(|dir| dir.join(filename))
+    .find(|file_path| file_path.exists())
+    .ok_or_else(|| Error::NotLocated {
+      filename: filename.to_owned(),
+      from: Some(from.to_path_buf()),
+    })
+}
+
+pub fn read_file<FilePath: AsRef<Path>>(file_path: FilePath) -> Result<String> {
+  let mut contents = String::new();
+  File::open(&file_path)
+    .and_then(|mut file| file.read_to_string(&mut contents))
+    .map_err(|source| Error::Io {
+      path: file_path.as_ref().to_path_buf(),
+      source,
+    })?;
+  Ok(contents)
 }
 
-pub fn read_json<Json, FilePath>(file_path: FilePath) -> Result<Json>
+/// Parse `contents` as `Json`, the error locates the failing field, see [ParseError].
+pub fn parse_json<Json>(contents: &str) -> std::result::Result<Json, ParseError>
 where
   Json: serde::de::DeserializeOwned,
-  FilePath: AsRef<Path>,
 {
-  let mut file = File::open(file_path)?;
-  let mut contents = String::new();
-  file.read_to_string(&mut contents)?;
-  let serialized_json = serde_json::from_str(&contents);
+  let mut deserializer = serde_json::Deserializer::from_str(contents);
+  let json = serde_path_to_error::deserialize(&mut deserializer)
+    .map_err(|error| ParseError::new(contents, error))?;
+  deserializer
+    .end()
+    .map_err(|error| ParseError::from_json_error(String::new(), contents, error))?;
+  Ok(json)
+}
+
+/// Collect the top-level keys of a JSON object in the order they appear in `contents`.
+pub fn object_keys(contents: &str) -> Vec<String> {
+  serde_json::from_str::<IndexMap<String, IgnoredAny>>(contents)
+    .map(|object| object.into_keys().collect())
+    .unwrap_or_default()
+}
 
-  match serialized_json {
-    Ok(json) => Ok(json),
-    Err(error) => Err(format_err!(error)),
+/// Reorder the top-level keys of `value` to follow `keys`. Keys which aren't listed in `keys` are kept at the end in their current order.
+pub fn sort_object_keys(value: &mut Value, keys: &[String]) {
+  if let Value::Object(object) = value {
+    let mut sorted = Map::with_capacity(object.len());
+    for key in keys {
+      if let Some(item) = object.shift_remove(key) {
+        sorted.insert(key.to_owned(), item);
+      }
+    }
+    sorted.append(object);
+    *object = sorted;
+  }
+}
+
+/// Serialize `json` following the formatting of `write_options`, without the final newline.
+pub fn to_json_string<Json>(json: Json, write_options: &WriteOptions) -> Result<String>
+where
+  Json: serde::Serialize,
+{
+  if !write_options.pretty {
+    return serde_json::to_string(&json).map_err(Error::Serialize);
   }
+  let indent = write_options.indent.as_string();
+  let mut buffer = Vec::new();
+  let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+  serde::Serialize::serialize(
+    &json,
+    &mut Serializer::with_formatter(&mut buffer, formatter),
+  )
+  .map_err(Error::Serialize)?;
+  // serde_json always produces valid UTF-8.
+  let json_content = String::from_utf8(buffer).expect("serialized JSON should be UTF-8");
+  Ok(match write_options.line_ending {
+    LineEnding::Lf => json_content,
+    line_ending => json_content.replace('\n', line_ending.as_str()),
+  })
 }
 
 pub fn write_json<Json, FilePath>(
@@ -49,19 +105,20 @@ where
   Json: serde::Serialize,
   FilePath: AsRef<Path>,
 {
-  let package_json = if write_options.pretty {
-    serde_json::to_string_pretty(&json)
-  } else {
-    serde_json::to_string(&json)
-  };
-
-  match package_json {
-    Ok(json_content) => {
-      File::create(file_path)?.write_all(json_content.as_bytes())?;
-      Ok(())
-    }
-    Err(error) => Err(format_err!(error)),
+  let mut json_content = to_json_string(json, write_options)?;
+  if write_options.final_newline {
+    json_content.push_str(write_options.line_ending.as_str());
   }
+  write_file(file_path, &json_content)
+}
+
+pub fn write_file<FilePath: AsRef<Path>>(file_path: FilePath, contents: &str) -> Result<()> {
+  File::create(&file_path)
+    .and_then(|mut file| file.write_all(contents.as_bytes()))
+    .map_err(|source| Error::Io {
+      path: file_path.as_ref().to_path_buf(),
+      source,
+    })
 }
 
 #[test]
@@ -130,7 +187,7 @@ fn test_read_json() {
   "name": "test"
 }"#;
   file.write_all(json.as_bytes()).expect("write json failed");
-  let read_json = read_json::<serde_json::Value, &Path>(&file_path).unwrap();
+  let read_json = parse_json::<serde_json::Value>(&read_file(&file_path).unwrap()).unwrap();
   assert_eq!(
     read_json,
     serde_json::json!({"name": "test"}),
@@ -140,7 +197,7 @@ fn test_read_json() {
 
 #[test]
 fn test_write_json() {
-  use self::write_options::WriteOptionsBuilder;
+  use self::write_options::{Indent, WriteOptionsBuilder};
   use serde::Serialize;
   use std::env::current_dir;
   use tempfile::tempdir_in;
@@ -172,6 +229,23 @@ fn test_write_json() {
   "name": "test"
 }"#,
     ),
+    (
+      WriteOptionsBuilder::default()
+        .indent(Indent::Tabs)
+        .line_ending(LineEnding::CrLf)
+        .final_newline(true)
+        .build()
+        .expect("build WriteOptions failed!"),
+      "{\r\n\t\"name\": \"test\"\r\n}\r\n",
+    ),
+    (
+      WriteOptionsBuilder::default()
+        .indent(Indent::Spaces(4))
+        .final_newline(true)
+        .build()
+        .expect("build WriteOptions failed!"),
+      "{\n    \"name\": \"test\"\n}\n",
+    ),
   ]
   .iter()
   .for_each(|(options, expect)| {
@@ -186,3 +260,25 @@ fn test_write_json() {
     assert_eq!(content, expect.to_string(), "write_json failed!");
   })
 }
+
+#[test]
+fn test_sort_object_keys() {
+  let contents = r#"{
+  "version": "1.0.0",
+  "scripts": {},
+  "name": "test"
+}"#;
+  let keys = object_keys(contents);
+  assert_eq!(keys, vec!["version", "scripts", "name"]);
+
+  let mut value = serde_json::json!({
+    "name": "test",
+    "private": true,
+    "version": "1.0.0",
+  });
+  sort_object_keys(&mut value, &keys);
+  assert_eq!(
+    value.as_object().unwrap().keys().collect::<Vec<_>>(),
+    vec!["version", "name", "private"]
+  );
+}
@@ -0,0 +1,401 @@
+use crate::error::{Error, Result};
+use crate::ParseError;
+use serde_json::{Map, Value};
+
+use super::to_json_string;
+use super::write_options::WriteOptions;
+
+/// A JSON value and the byte span it covers in the source text.
+#[derive(Debug)]
+pub struct Node {
+  pub start: usize,
+  pub end: usize,
+  pub kind: NodeKind,
+}
+
+#[derive(Debug)]
+pub enum NodeKind {
+  Object(Vec<Member>),
+  Array(Vec<Node>),
+  Scalar,
+}
+
+/// A `"key": value` pair of an object.
+#[derive(Debug)]
+pub struct Member {
+  pub key: String,
+  pub key_start: usize,
+  pub key_end: usize,
+  pub value: Node,
+}
+
+/// Parse `source` into a tree of spans. The source is expected to be valid JSON, the values themselves are not decoded except the object keys.
+pub fn parse(source: &str) -> Result<Node> {
+  let mut parser = Parser {
+    source,
+    bytes: source.as_bytes(),
+    pos: 0,
+  };
+  parser.skip_whitespace();
+  let node = parser.parse_value()?;
+  parser.skip_whitespace();
+  if parser.pos != parser.bytes.len() {
+    return Err(parser.error("trailing characters"));
+  }
+  Ok(node)
+}
+
+struct Parser<'a> {
+  source: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl Parser<'_> {
+  fn error(&self, message: &str) -> Error {
+    Error::Parse(ParseError::at(self.source, self.pos, message))
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.bytes.get(self.pos).copied()
+  }
+
+  fn skip_whitespace(&mut self) {
+    while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+      self.pos += 1;
+    }
+  }
+
+  fn expect(&mut self, byte: u8) -> Result<()> {
+    if self.peek() == Some(byte) {
+      self.pos += 1;
+      Ok(())
+    } else {
+      Err(self.error(&format!("expected `{}`", byte as char)))
+    }
+  }
+
+  fn parse_value(&mut self) -> Result<Node> {
+    let start = self.pos;
+    let kind = match self.peek() {
+      Some(b'{') => NodeKind::Object(self.parse_object()?),
+      Some(b'[') => NodeKind::Array(self.parse_array()?),
+      Some(b'"') => {
+        self.parse_string()?;
+        NodeKind::Scalar
+      }
+      Some(_) => {
+        while let Some(byte) = self.peek() {
+          if matches!(byte, b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r') {
+            break;
+          }
+          self.pos += 1;
+        }
+        if self.pos == start {
+          return Err(self.error("expected a value"));
+        }
+        NodeKind::Scalar
+      }
+      None => return Err(self.error("unexpected end of input")),
+    };
+    Ok(Node {
+      start,
+      end: self.pos,
+      kind,
+    })
+  }
+
+  fn parse_string(&mut self) -> Result<()> {
+    self.expect(b'"')?;
+    while let Some(byte) = self.peek() {
+      self.pos += 1;
+      match byte {
+        b'\\' => self.pos += 1,
+        b'"' => return Ok(()),
+        _ => {}
+      }
+    }
+    Err(self.error("unterminated string"))
+  }
+
+  fn parse_object(&mut self) -> Result<Vec<Member>> {
+    let mut members = Vec::new();
+    self.expect(b'{')?;
+    self.skip_whitespace();
+    if self.peek() == Some(b'}') {
+      self.pos += 1;
+      return Ok(members);
+    }
+    loop {
+      self.skip_whitespace();
+      let key_start = self.pos;
+      self.parse_string()?;
+      let key_end = self.pos;
+      let key = serde_json::from_str(&self.source[key_start..key_end])
+        .map_err(|_| self.error("invalid key"))?;
+      self.skip_whitespace();
+      self.expect(b':')?;
+      self.skip_whitespace();
+      let value = self.parse_value()?;
+      members.push(Member {
+        key,
+        key_start,
+        key_end,
+        value,
+      });
+      self.skip_whitespace();
+      match self.peek() {
+        Some(b',') => self.pos += 1,
+        Some(b'}') => {
+          self.pos += 1;
+          return Ok(members);
+        }
+        _ => return Err(self.error("expected `,` or `}`")),
+      }
+    }
+  }
+
+  fn parse_array(&mut self) -> Result<Vec<Node>> {
+    let mut items = Vec::new();
+    self.expect(b'[')?;
+    self.skip_whitespace();
+    if self.peek() == Some(b']') {
+      self.pos += 1;
+      return Ok(items);
+    }
+    loop {
+      self.skip_whitespace();
+      items.push(self.parse_value()?);
+      self.skip_whitespace();
+      match self.peek() {
+        Some(b',') => self.pos += 1,
+        Some(b']') => {
+          self.pos += 1;
+          return Ok(items);
+        }
+        _ => return Err(self.error("expected `,` or `]`")),
+      }
+    }
+  }
+}
+
+/// Serialize `value` as it is placed on a line indented with `base`.
+fn render(value: &Value, base: &str, style: &WriteOptions) -> Result<String> {
+  let rendered = to_json_string(value, style)?;
+  if style.pretty {
+    Ok(rendered.replace('\n', &format!("\n{}", base)))
+  } else {
+    Ok(rendered)
+  }
+}
+
+/// A replacement of `source[start..end]`.
+struct Edit {
+  start: usize,
+  end: usize,
+  text: String,
+}
+
+/// Rewrite `source` so that it represents `new` instead of `old`, touching only the spans of the values which differ between them.
+///
+/// `old` is the value `source` was decoded to. It may slightly differ from `source` (e.g. default values were filled in during decoding), the fields which are equal in `old` and `new` are always kept as they are in `source`.
+pub fn patch(source: &str, old: &Value, new: &Value) -> Result<String> {
+  let root = parse(source)?;
+  let mut style = WriteOptions::default();
+  style.detect_from(source);
+  let mut patcher = Patcher {
+    source,
+    style,
+    edits: Vec::new(),
+  };
+  patcher.diff(&root, old, new)?;
+
+  let mut edits = patcher.edits;
+  edits.sort_by(|a, b| b.start.cmp(&a.start).then(b.end.cmp(&a.end)));
+  let mut patched = source.to_owned();
+  for edit in edits {
+    patched.replace_range(edit.start..edit.end, &edit.text);
+  }
+  Ok(patched)
+}
+
+struct Patcher<'a> {
+  source: &'a str,
+  style: WriteOptions,
+  edits: Vec<Edit>,
+}
+
+impl Patcher<'_> {
+  /// The leading whitespace of the line which contains `pos`.
+  fn line_indent(&self, pos: usize) -> &str {
+    let line_start = self.source[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &self.source[line_start..pos];
+    &line[..line.len() - line.trim_start().len()]
+  }
+
+  fn replace(&mut self, node: &Node, value: &Value) -> Result<()> {
+    let text = render(value, self.line_indent(node.start), &self.style)?;
+    self.edits.push(Edit {
+      start: node.start,
+      end: node.end,
+      text,
+    });
+    Ok(())
+  }
+
+  fn diff(&mut self, node: &Node, old: &Value, new: &Value) -> Result<()> {
+    if old == new {
+      return Ok(());
+    }
+    match (&node.kind, old, new) {
+      (NodeKind::Object(members), Value::Object(old), Value::Object(new))
+        if !members.is_empty() =>
+      {
+        self.diff_object(node, members, old, new)
+      }
+      (NodeKind::Array(items), Value::Array(old), Value::Array(new))
+        if items.len() == old.len() && old.len() == new.len() =>
+      {
+        for ((item, old), new) in items.iter().zip(old).zip(new) {
+          self.diff(item, old, new)?;
+        }
+        Ok(())
+      }
+      _ => self.replace(node, new),
+    }
+  }
+
+  fn diff_object(
+    &mut self,
+    node: &Node,
+    members: &[Member],
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+  ) -> Result<()> {
+    let mut removed = vec![false; members.len()];
+    for (index, member) in members.iter().enumerate() {
+      match (old.get(&member.key), new.get(&member.key)) {
+        (Some(_), None) => removed[index] = true,
+        (Some(old), Some(new)) => self.diff(&member.value, old, new)?,
+        (None, Some(new)) => {
+          let current: Value = serde_json::from_str(
+            &self.source[member.value.start..member.value.end],
+          )
+          .map_err(|error| {
+            Error::Parse(ParseError::at(
+              self.source,
+              member.value.start,
+              &error.to_string(),
+            ))
+          })?;
+          self.diff(&member.value, &current, new)?;
+        }
+        // the field is only present in the source, e.g. it's skipped during serialization.
+        (None, None) => {}
+      }
+    }
+
+    let inserted = new
+      .iter()
+      .filter(|(key, value)| {
+        !members.iter().any(|member| &member.key == *key) && old.get(*key) != Some(*value)
+      })
+      .collect::<Vec<_>>();
+
+    if removed.iter().all(|removed| *removed) {
+      return self.replace(node, &Value::Object(new.clone()));
+    }
+
+    // the leading removed members are dropped along with the separator after them, the others are dropped along with the separator before them.
+    let leading = removed.iter().take_while(|removed| **removed).count();
+    if leading > 0 {
+      self.edits.push(Edit {
+        start: members[0].key_start,
+        end: members[leading].key_start,
+        text: String::new(),
+      });
+    }
+    for index in leading..members.len() {
+      if removed[index] {
+        self.edits.push(Edit {
+          start: members[index - 1].value.end,
+          end: members[index].value.end,
+          text: String::new(),
+        });
+      }
+    }
+
+    if !inserted.is_empty() {
+      let last = members.last().expect("members should not be empty");
+      let separator = &self.source[last.key_end..last.value.start];
+      let lead = &self.source[..last.key_start];
+      let mut lead = &lead[lead.trim_end().len()..];
+      // there is no separator between the members of a single-line object yet
+      if members.len() == 1 && !lead.contains('\n') {
+        lead = if separator.ends_with(' ') { " " } else { "" };
+      }
+      let indent = self.line_indent(last.key_start).to_owned();
+      let mut text = String::new();
+      for (key, value) in inserted {
+        text.push(',');
+        text.push_str(lead);
+        text.push_str(&serde_json::to_string(key).map_err(Error::Serialize)?);
+        text.push_str(separator);
+        text.push_str(&render(value, &indent, &self.style)?);
+      }
+      self.edits.push(Edit {
+        start: last.value.end,
+        end: last.value.end,
+        text,
+      });
+    }
+    Ok(())
+  }
+}
+
+#[test]
+fn test_parse_spans() {
+  let source = r#"{ "a": [1, true], "b\"": {"c": null} }"#;
+  let root = parse(source).unwrap();
+  let NodeKind::Object(members) = root.kind else {
+    panic!("expected an object");
+  };
+  assert_eq!(members.len(), 2);
+  assert_eq!(members[0].key, "a");
+  assert_eq!(
+    &source[members[0].value.start..members[0].value.end],
+    "[1, true]"
+  );
+  assert_eq!(members[1].key, "b\"");
+  assert_eq!(
+    &source[members[1].value.start..members[1].value.end],
+    r#"{"c": null}"#
+  );
+  assert!(parse(r#"{"a": 1"#).is_err());
+}
+
+#[test]
+fn test_patch() {
+  let source = "{\r\n\t\"name\" : \"test\",\r\n\t\"version\" : \"1.0.0\",\r\n\t\"scripts\" : {\"build\": \"tsc\"},\r\n\t\"files\" : [ \"dist\" ]\r\n}";
+  let old: Value = serde_json::from_str(source).unwrap();
+
+  assert_eq!(patch(source, &old, &old).unwrap(), source);
+
+  let mut new = old.clone();
+  new["version"] = "1.0.1".into();
+  new["scripts"]["test"] = "jest".into();
+  new["files"].as_array_mut().unwrap().push("lib".into());
+  new["bin"] = serde_json::json!({ "cli": "./cli.js" });
+  assert_eq!(
+    patch(source, &old, &new).unwrap(),
+    "{\r\n\t\"name\" : \"test\",\r\n\t\"version\" : \"1.0.1\",\r\n\t\"scripts\" : {\"build\": \"tsc\", \"test\": \"jest\"},\r\n\t\"files\" : [\r\n\t\t\"dist\",\r\n\t\t\"lib\"\r\n\t],\r\n\t\"bin\" : {\r\n\t\t\"cli\": \"./cli.js\"\r\n\t}\r\n}"
+  );
+
+  let mut new = old.clone();
+  new.as_object_mut().unwrap().shift_remove("name");
+  new.as_object_mut().unwrap().shift_remove("scripts");
+  assert_eq!(
+    patch(source, &old, &new).unwrap(),
+    "{\r\n\t\"version\" : \"1.0.0\",\r\n\t\"files\" : [ \"dist\" ]\r\n}"
+  );
+}
@@ -0,0 +1,360 @@
+use serde_json::error::Category;
+use serde_path_to_error::Segment;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The number of lines shown around the failing line in [ParseError::code_frame].
+const CONTEXT_LINES: usize = 2;
+
+/// A failure of parsing `package.json` (or `pnpm-workspace.yaml`), which locates the failing field both in the JSON document and in the source text.
+/// ```
+/// use package_json::{Error, PackageJsonManager};
+/// # let dir = tempfile::tempdir_in(std::env::current_dir().unwrap()).expect("create temp_dir failed!");
+/// # let file_path = dir.path().join("package.json");
+/// # std::fs::write(&file_path, "{\n  \"name\": \"test\",\n  \"version\": \"1.0.0\",\n  \"author\": 42\n}\n").unwrap();
+///
+/// let mut manager = PackageJsonManager::with_file_path(&file_path);
+/// let Err(Error::Parse(error)) = manager.read_ref() else {
+///   panic!("package.json should be malformed");
+/// };
+/// assert_eq!(error.pointer, "/author");
+/// assert_eq!((error.line, error.column), (4, 13));
+/// println!("{}", error.code_frame());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+  /// The [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) of the failing field, e.g. `/contributors/0/email`. It's empty when the document itself is malformed or isn't an object.
+  pub pointer: String,
+  /// The 1-based line where the error is detected.
+  pub line: usize,
+  /// The 1-based column where the error is detected.
+  pub column: usize,
+  /// The message of the underlying `serde_json` error, without its position.
+  pub message: String,
+  /// The parsed file, if it's read from the disk.
+  pub file_path: Option<PathBuf>,
+  source: String,
+}
+
+impl ParseError {
+  pub(crate) fn new(source: &str, error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+    let keys = path_keys(error.path());
+    let pointer = to_pointer(&keys);
+    let error = error.into_inner();
+    let category = error.classify();
+    let mut parse_error = Self::from_json_error(pointer, source, error);
+    // serde_json reports a data error where it stops reading, which is after the whole value for an untagged enum, the start of the failing value is more precise. A syntax error is kept where it's detected.
+    if category == Category::Data && !keys.is_empty() {
+      if let Some(offset) = locate_value(source, &keys) {
+        parse_error.move_to(offset);
+      }
+    }
+    parse_error
+  }
+
+  /// A failure of parsing the YAML `source`, e.g. `pnpm-workspace.yaml`.
+  pub(crate) fn from_yaml_error(
+    source: &str,
+    error: serde_path_to_error::Error<serde_norway::Error>,
+  ) -> Self {
+    let pointer = to_pointer(&path_keys(error.path()));
+    let error = error.into_inner();
+    let mut message = error.to_string();
+    // serde_norway appends the position to the message, it's kept in the dedicated fields instead.
+    if let Some(index) = message.rfind(" at line ") {
+      message.truncate(index);
+    }
+    let offset = error.location().map_or(0, |location| location.index());
+    let mut parse_error = Self::at(source, offset, &message);
+    parse_error.pointer = pointer;
+    parse_error
+  }
+
+  /// An error detected at the byte `offset` of `source`.
+  pub(crate) fn at(source: &str, offset: usize, message: &str) -> Self {
+    let mut parse_error = Self {
+      pointer: String::new(),
+      line: 0,
+      column: 0,
+      message: message.to_owned(),
+      file_path: None,
+      source: source.to_owned(),
+    };
+    parse_error.move_to(offset.min(source.len()));
+    parse_error
+  }
+
+  fn move_to(&mut self, offset: usize) {
+    let before = &self.source[..offset];
+    self.line = before.matches('\n').count() + 1;
+    self.column = before[before.rfind('\n').map_or(0, |index| index + 1)..]
+      .chars()
+      .count()
+      + 1;
+  }
+
+  pub(crate) fn from_json_error(pointer: String, source: &str, error: serde_json::Error) -> Self {
+    let mut message = error.to_string();
+    // serde_json appends the position to the message, it's kept in the dedicated fields instead.
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    if message.ends_with(&position) {
+      message.truncate(message.len() - position.len());
+    }
+    Self {
+      pointer,
+      line: error.line(),
+      column: error.column(),
+      message,
+      file_path: None,
+      source: source.to_owned(),
+    }
+  }
+
+  pub(crate) fn with_file_path(mut self, file_path: &Path) -> Self {
+    self.file_path = Some(file_path.to_path_buf());
+    self
+  }
+
+  /// Render the lines around the failing position, with a `>` marking the failing line and a `^` marking the column.
+  /// ```text
+  ///   2 |   "name": "test",
+  ///   3 |   "version": "1.0.0",
+  /// > 4 |   "author": 42
+  ///     |             ^
+  ///   5 | }
+  /// ```
+  pub fn code_frame(&self) -> String {
+    let lines = self.source.lines().collect::<Vec<_>>();
+    if lines.is_empty() || self.line == 0 {
+      return String::new();
+    }
+    let line = self.line.min(lines.len());
+    let start = line.saturating_sub(CONTEXT_LINES).max(1);
+    let end = (line + CONTEXT_LINES).min(lines.len());
+    let width = end.to_string().len();
+    let mut frame = Vec::new();
+    for number in start..=end {
+      let text = lines[number - 1];
+      match number == line {
+        true => {
+          frame.push(format!("> {:>width$} | {}", number, text));
+          let offset = text
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|char| match char {
+              '\t' => '\t',
+              _ => ' ',
+            })
+            .collect::<String>();
+          frame.push(format!("  {:>width$} | {}^", "", offset));
+        }
+        false => frame.push(format!("  {:>width$} | {}", number, text)),
+      }
+    }
+    frame.join("\n")
+  }
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)?;
+    if !self.pointer.is_empty() {
+      write!(f, " at {}", self.pointer)?;
+    }
+    match &self.file_path {
+      Some(file_path) => write!(
+        f,
+        " ({}:{}:{})",
+        file_path.display(),
+        self.line,
+        self.column
+      )?,
+      None => write!(f, " (line {}, column {})", self.line, self.column)?,
+    }
+    let frame = self.code_frame();
+    if !frame.is_empty() {
+      write!(f, "\n\n{}", frame)?;
+    }
+    Ok(())
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+fn path_keys(path: &serde_path_to_error::Path) -> Vec<String> {
+  path
+    .iter()
+    .filter_map(|segment| match segment {
+      Segment::Seq { index } => Some(index.to_string()),
+      Segment::Map { key } => Some(key.to_owned()),
+      Segment::Enum { variant } => Some(variant.to_owned()),
+      Segment::Unknown => None,
+    })
+    .collect()
+}
+
+fn to_pointer(keys: &[String]) -> String {
+  keys
+    .iter()
+    .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+    .collect()
+}
+
+/// Find the byte offset of the value at `keys` in the JSON `source`.
+fn locate_value(source: &str, keys: &[String]) -> Option<usize> {
+  let bytes = source.as_bytes();
+  let mut offset = skip_whitespace(bytes, 0);
+  for key in keys {
+    match bytes.get(offset)? {
+      b'{' => {
+        offset = skip_whitespace(bytes, offset + 1);
+        loop {
+          let end = skip_string(bytes, offset)?;
+          let name = serde_json::from_str::<String>(&source[offset..end]).ok()?;
+          offset = skip_whitespace(bytes, end);
+          (bytes.get(offset)? == &b':').then_some(())?;
+          offset = skip_whitespace(bytes, offset + 1);
+          if &name == key {
+            break;
+          }
+          offset = skip_whitespace(bytes, skip_value(bytes, offset)?);
+          (bytes.get(offset)? == &b',').then_some(())?;
+          offset = skip_whitespace(bytes, offset + 1);
+        }
+      }
+      b'[' => {
+        let index = key.parse::<usize>().ok()?;
+        offset = skip_whitespace(bytes, offset + 1);
+        for _ in 0..index {
+          offset = skip_whitespace(bytes, skip_value(bytes, offset)?);
+          (bytes.get(offset)? == &b',').then_some(())?;
+          offset = skip_whitespace(bytes, offset + 1);
+        }
+      }
+      _ => return None,
+    }
+  }
+  Some(offset)
+}
+
+fn skip_whitespace(bytes: &[u8], offset: usize) -> usize {
+  offset
+    + bytes[offset.min(bytes.len())..]
+      .iter()
+      .take_while(|byte| byte.is_ascii_whitespace())
+      .count()
+}
+
+/// Return the offset after the string starting at `offset`.
+fn skip_string(bytes: &[u8], offset: usize) -> Option<usize> {
+  (bytes.get(offset)? == &b'"').then_some(())?;
+  let mut index = offset + 1;
+  loop {
+    match bytes.get(index)? {
+      b'\\' => index += 2,
+      b'"' => return Some(index + 1),
+      _ => index += 1,
+    }
+  }
+}
+
+/// Return the offset after the value starting at `offset`.
+fn skip_value(bytes: &[u8], offset: usize) -> Option<usize> {
+  match bytes.get(offset)? {
+    b'"' => skip_string(bytes, offset),
+    b'{' | b'[' => {
+      let mut depth = 0usize;
+      let mut index = offset;
+      loop {
+        match bytes.get(index)? {
+          b'"' => {
+            index = skip_string(bytes, index)?;
+            continue;
+          }
+          b'{' | b'[' => depth += 1,
+          b'}' | b']' => {
+            depth -= 1;
+            if depth == 0 {
+              return Some(index + 1);
+            }
+          }
+          _ => {}
+        }
+        index += 1;
+      }
+    }
+    _ => Some(
+      offset
+        + bytes[offset..]
+          .iter()
+          .take_while(|byte| !matches!(byte, b',' | b'}' | b']') && !byte.is_ascii_whitespace())
+          .count(),
+    ),
+  }
+}
+
+#[test]
+fn test_parse_error() {
+  use crate::fs::parse_json;
+  use crate::PackageJson;
+
+  let source = r#"{
+  "name": "test",
+  "version": "1.0.0",
+  "scripts": { "test": "echo \"[}\"" },
+  "contributors": [
+    "Barney Rubble",
+    { "name": "Fred", "email": 42 }
+  ]
+}
+"#;
+  let error = parse_json::<PackageJson>(source).unwrap_err();
+  assert_eq!(error.pointer, "/contributors/1");
+  assert_eq!((error.line, error.column), (7, 5));
+  assert_eq!(
+    error.to_string(),
+    r#"data did not match any variant of untagged enum PackagePeople at /contributors/1 (line 7, column 5)
+
+  5 |   "contributors": [
+  6 |     "Barney Rubble",
+> 7 |     { "name": "Fred", "email": 42 }
+    |     ^
+  8 |   ]
+  9 | }"#
+  );
+
+  let error =
+    parse_json::<PackageJson>(r#"{"name": "test", "dependencies": {"a": 1}}"#).unwrap_err();
+  assert_eq!(error.pointer, "/dependencies/a");
+  assert_eq!((error.line, error.column), (1, 40));
+  assert_eq!(
+    error.message,
+    "invalid type: integer `1`, expected a string"
+  );
+
+  let source = "{\n  \"name\": \"test\"\n  \"version\": \"1.0.0\"\n}";
+  let error = parse_json::<PackageJson>(source)
+    .unwrap_err()
+    .with_file_path(Path::new("package.json"));
+  assert_eq!(error.pointer, "");
+  assert_eq!(
+    error.to_string(),
+    r#"expected `,` or `}` (package.json:3:3)
+
+  1 | {
+  2 |   "name": "test"
+> 3 |   "version": "1.0.0"
+    |   ^
+  4 | }"#
+  );
+
+  // a syntax error inside a field is reported where it's detected, rather than at the start of the field.
+  let source = "{\n  \"name\": \"test\",\n  \"version\": \"1.0.0\",\n  \"scripts\": {\n    \"a\": \"b\"\n    \"c\": \"d\"\n  }\n}";
+  let error = parse_json::<PackageJson>(source).unwrap_err();
+  assert_eq!(error.message, "expected `,` or `}`");
+  assert_eq!((error.line, error.column), (6, 5));
+
+  let error = parse_json::<PackageJson>(r#"{"name": "test", "version": "1.0.0"} {}"#).unwrap_err();
+  assert_eq!(error.message, "trailing characters");
+  assert_eq!((error.line, error.column), (1, 38));
+}
@@ -6,4 +6,136 @@ pub struct WriteOptions {
   /// Set this to `false` to generate a compact JSON, instead of a pretty-printed JSON.
   #[builder(default = "true")]
   pub pretty: bool,
+  /// The indentation of a pretty-printed JSON, defaults to two spaces.
+  #[builder(default)]
+  pub indent: Indent,
+  /// The line ending of a pretty-printed JSON, defaults to `\n`.
+  #[builder(default)]
+  pub line_ending: LineEnding,
+  /// Set this to `true` to end the file with a line ending, like what npm does.
+  #[builder(default = "false")]
+  pub final_newline: bool,
+  /// Set this to `true` to detect [pretty][WriteOptions::pretty], [indent][WriteOptions::indent], [line_ending][WriteOptions::line_ending] and [final_newline][WriteOptions::final_newline] from the file when it's read, and reuse them when it's written.
+  #[builder(default = "false")]
+  pub detect_format: bool,
+  /// Set this to `true` to write only the fields changed since the file was read, the rest of the file is kept byte-for-byte.
+  ///
+  /// It only takes effect when the content was read from a file, otherwise the whole content is serialized.
+  #[builder(default = "false")]
+  pub preserve_format: bool,
+  /// Set this to convert all entries of `author`, `contributors` and `maintainers` into the same shape before the file is written, see [PackageJson::normalize_people](crate::PackageJson::normalize_people).
+  #[builder(default, setter(strip_option))]
+  pub people_format: Option<PeopleFormat>,
+}
+
+impl WriteOptions {
+  /// Update the formatting options to follow the existing file `contents`.
+  pub fn detect_from(&mut self, contents: &str) {
+    self.pretty = contents.trim().contains('\n');
+    self.line_ending = if contents.contains("\r\n") {
+      LineEnding::CrLf
+    } else {
+      LineEnding::Lf
+    };
+    self.final_newline = contents.ends_with('\n');
+    if let Some(indent) = contents
+      .lines()
+      .skip(1)
+      .map(|line| &line[..line.len() - line.trim_start().len()])
+      .find(|indent| !indent.is_empty())
+    {
+      self.indent = if indent.starts_with('\t') {
+        Indent::Tabs
+      } else {
+        Indent::Spaces(indent.len())
+      };
+    }
+  }
+}
+
+/// The indentation of a pretty-printed JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+  /// Indent with the given number of spaces.
+  Spaces(usize),
+  /// Indent with a tab.
+  Tabs,
+}
+
+impl Default for Indent {
+  fn default() -> Self {
+    Self::Spaces(2)
+  }
+}
+
+impl Indent {
+  /// The whitespace of one indentation level.
+  pub fn as_string(&self) -> String {
+    match self {
+      Self::Spaces(width) => " ".repeat(*width),
+      Self::Tabs => "\t".to_owned(),
+    }
+  }
+}
+
+/// The line ending of a pretty-printed JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+  /// `\n`, used on Linux and macOS.
+  #[default]
+  Lf,
+  /// `\r\n`, used on Windows.
+  CrLf,
+}
+
+impl LineEnding {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Lf => "\n",
+      Self::CrLf => "\r\n",
+    }
+  }
+}
+
+/// The shape of a person in the `author`, `contributors` and `maintainers` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeopleFormat {
+  /// The shorthand string like `Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)`.
+  Literal,
+  /// The object like `{ "name": "Barney Rubble", "email": "b@rubble.com" }`.
+  Record,
+}
+
+#[test]
+fn test_detect_from() {
+  for (contents, pretty, indent, line_ending, final_newline) in [
+    (
+      r#"{"name":"test"}"#,
+      false,
+      Indent::Spaces(2),
+      LineEnding::Lf,
+      false,
+    ),
+    (
+      "{\n    \"name\": \"test\"\n}\n",
+      true,
+      Indent::Spaces(4),
+      LineEnding::Lf,
+      true,
+    ),
+    (
+      "{\r\n\t\"name\": \"test\"\r\n}",
+      true,
+      Indent::Tabs,
+      LineEnding::CrLf,
+      false,
+    ),
+  ] {
+    let mut options = WriteOptions::default();
+    options.detect_from(contents);
+    assert_eq!(options.pretty, pretty);
+    assert_eq!(options.indent, indent);
+    assert_eq!(options.line_ending, line_ending);
+    assert_eq!(options.final_newline, final_newline);
+  }
 }
@@ -0,0 +1,495 @@
+//! Repository URLs of the well-known git hosts, equivalent to [hosted-git-info](https://github.com/npm/hosted-git-info).
+
+use crate::{DependencySpec, GitHost, PackageJson, PackageRepository, PackageRepositoryRecord};
+
+/// A repository on one of the [GitHost]s, parsed from a shorthand like `github:user/repo`, `user/repo` or `gist:id`, or from a full git URL like `git+https://github.com/user/repo.git` and `git@gitlab.com:group/subgroup/repo.git`.
+///
+/// ```
+/// use package_json::HostedGitInfo;
+///
+/// let info = HostedGitInfo::parse("npm/cli#v10.0.0").unwrap();
+/// assert_eq!(info.https_url(), "git+https://github.com/npm/cli.git#v10.0.0");
+/// assert_eq!(info.browse_url(), "https://github.com/npm/cli/tree/v10.0.0");
+/// assert_eq!(info.bugs_url(), "https://github.com/npm/cli/issues");
+/// assert_eq!(info.tarball_url(), "https://codeload.github.com/npm/cli/tar.gz/v10.0.0");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostedGitInfo {
+  pub host: GitHost,
+  /// The owner of the repository, which is optional for a gist. It may contain `/` for a GitLab subgroup.
+  pub user: Option<String>,
+  /// The repository name, or the id of a gist.
+  pub project: String,
+  /// A branch, tag or commit hash.
+  pub committish: Option<String>,
+  /// The subdirectory where the package lives in a monorepo, see [PackageRepositoryRecord::directory].
+  pub directory: Option<String>,
+}
+
+impl HostedGitInfo {
+  /// Parse a shorthand or a git URL of a hosted repository, it returns [None] when the repository isn't on a known host.
+  pub fn parse(url: &str) -> Option<Self> {
+    let url = url.trim();
+    if let Ok(DependencySpec::Hosted {
+      host,
+      user,
+      project,
+      committish,
+    }) = DependencySpec::parse(url)
+    {
+      return Some(Self {
+        host: host.unwrap_or(GitHost::GitHub),
+        user,
+        project: strip_git(&project),
+        committish: committish.map(|committish| committish.to_string()[1..].to_owned()),
+        directory: None,
+      });
+    }
+    Self::parse_url(url)
+  }
+
+  fn parse_url(url: &str) -> Option<Self> {
+    let (url, fragment) = match url.split_once('#') {
+      Some((url, fragment)) => (url, Some(fragment).filter(|fragment| !fragment.is_empty())),
+      None => (url, None),
+    };
+    let url = url.strip_prefix("git+").unwrap_or(url);
+    let scp_like = !url.contains("://");
+    let (domain, path) = match url.split_once("://") {
+      Some((scheme, rest)) => {
+        if !matches!(scheme, "https" | "http" | "git" | "ssh") {
+          return None;
+        }
+        let (authority, path) = rest.split_once('/')?;
+        (authority.rsplit('@').next()?, path)
+      }
+      // scp-like url, e.g. `git@github.com:npm/cli.git`, or a shorthand which isn't accepted as a dependency specifier, e.g. `gitlab:group/subgroup/repo`
+      None => {
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') {
+          return None;
+        }
+        (authority.rsplit('@').next()?, path)
+      }
+    };
+    let domain = domain.strip_prefix("www.").unwrap_or(domain);
+    let host = [
+      GitHost::GitHub,
+      GitHost::GitLab,
+      GitHost::Bitbucket,
+      GitHost::Gist,
+    ]
+    .into_iter()
+    .find(|host| domain_of(*host) == domain || scp_like && host.shortcut() == domain)?;
+
+    let path = path.split('?').next().unwrap_or_default();
+    let segments = path
+      .split('/')
+      .filter(|segment| !segment.is_empty())
+      .collect::<Vec<_>>();
+    let (user, project, committish) = match host {
+      GitHost::GitHub => match segments.as_slice() {
+        [user, project] => (Some(user.to_string()), strip_git(project), None),
+        [user, project, "tree", committish @ ..] if !committish.is_empty() => (
+          Some(user.to_string()),
+          strip_git(project),
+          Some(committish.join("/")),
+        ),
+        _ => return None,
+      },
+      GitHost::GitLab => {
+        if segments.contains(&"-") {
+          return None;
+        }
+        match segments.split_last() {
+          Some((project, user)) if !user.is_empty() => {
+            (Some(user.join("/")), strip_git(project), None)
+          }
+          _ => return None,
+        }
+      }
+      GitHost::Bitbucket => match segments.as_slice() {
+        [_, _, "get", ..] => return None,
+        [user, project, ..] => (Some(user.to_string()), strip_git(project), None),
+        _ => return None,
+      },
+      GitHost::Gist => match segments.as_slice() {
+        [_, _, "raw", ..] => return None,
+        [project] => (None, strip_git(project), None),
+        [user, project, ..] => (Some(user.to_string()), strip_git(project), None),
+        _ => return None,
+      },
+    };
+    if project.is_empty() {
+      return None;
+    }
+
+    Some(Self {
+      host,
+      user,
+      project,
+      committish: committish.or_else(|| fragment.map(|fragment| fragment.to_owned())),
+      directory: None,
+    })
+  }
+
+  /// Set the subdirectory where the package lives in a monorepo.
+  pub fn with_directory<Directory: Into<String>>(mut self, directory: Directory) -> Self {
+    let directory = directory.into();
+    let directory = directory.trim_matches('/');
+    self.directory = Some(directory.to_owned()).filter(|directory| !directory.is_empty());
+    self
+  }
+
+  fn repository_path(&self) -> String {
+    match (&self.user, self.host) {
+      (_, GitHost::Gist) | (None, _) => self.project.clone(),
+      (Some(user), _) => format!("{}/{}", user, self.project),
+    }
+  }
+
+  fn fragment(&self) -> String {
+    self
+      .committish
+      .as_ref()
+      .map(|committish| format!("#{}", committish))
+      .unwrap_or_default()
+  }
+
+  /// The shorthand, e.g. `github:npm/cli`.
+  pub fn shortcut(&self) -> String {
+    let path = match &self.user {
+      Some(user) => format!("{}/{}", user, self.project),
+      None => self.project.clone(),
+    };
+    format!("{}:{}{}", self.host.shortcut(), path, self.fragment())
+  }
+
+  /// The canonical git URL over HTTPS, e.g. `git+https://github.com/npm/cli.git`, which npm writes to the `repository` field.
+  pub fn https_url(&self) -> String {
+    format!(
+      "git+https://{}/{}.git{}",
+      domain_of(self.host),
+      self.repository_path(),
+      self.fragment()
+    )
+  }
+
+  /// The git URL over SSH, e.g. `git+ssh://git@github.com/npm/cli.git`.
+  pub fn ssh_url(&self) -> String {
+    format!(
+      "git+ssh://git@{}/{}.git{}",
+      domain_of(self.host),
+      self.repository_path(),
+      self.fragment()
+    )
+  }
+
+  /// The web page of the repository. It links to the [directory][HostedGitInfo::directory] when there is one.
+  pub fn browse_url(&self) -> String {
+    let base = format!(
+      "https://{}/{}",
+      domain_of(self.host),
+      self.repository_path()
+    );
+    let committish = self.committish.as_deref();
+    match (self.host, &self.directory) {
+      (GitHost::Gist, _) => match committish {
+        Some(committish) => format!("{}/{}", base, committish),
+        None => base,
+      },
+      (GitHost::Bitbucket, Some(directory)) => {
+        format!(
+          "{}/src/{}/{}",
+          base,
+          committish.unwrap_or("HEAD"),
+          directory
+        )
+      }
+      (_, Some(directory)) => {
+        format!(
+          "{}/tree/{}/{}",
+          base,
+          committish.unwrap_or("HEAD"),
+          directory
+        )
+      }
+      (GitHost::Bitbucket, None) => match committish {
+        Some(committish) => format!("{}/src/{}", base, committish),
+        None => base,
+      },
+      (_, None) => match committish {
+        Some(committish) => format!("{}/tree/{}", base, committish),
+        None => base,
+      },
+    }
+  }
+
+  /// The documentation of the repository, which is the README of the [browse_url][HostedGitInfo::browse_url], e.g. `https://github.com/npm/cli#readme`.
+  pub fn docs_url(&self) -> String {
+    match self.host {
+      GitHost::Gist => self.browse_url(),
+      _ => format!("{}#readme", self.browse_url()),
+    }
+  }
+
+  /// The issue tracker of the repository, e.g. `https://github.com/npm/cli/issues`.
+  pub fn bugs_url(&self) -> String {
+    match self.host {
+      GitHost::Gist => format!("https://{}/{}", domain_of(self.host), self.project),
+      _ => format!(
+        "https://{}/{}/issues",
+        domain_of(self.host),
+        self.repository_path()
+      ),
+    }
+  }
+
+  /// The URL to download a tarball of the [committish][HostedGitInfo::committish], which defaults to `HEAD`.
+  pub fn tarball_url(&self) -> String {
+    let committish = self.committish.as_deref().unwrap_or("HEAD");
+    match self.host {
+      GitHost::GitHub => format!(
+        "https://codeload.github.com/{}/tar.gz/{}",
+        self.repository_path(),
+        committish
+      ),
+      GitHost::GitLab => format!(
+        "https://gitlab.com/{}/repository/archive.tar.gz?ref={}",
+        self.repository_path(),
+        committish
+      ),
+      GitHost::Bitbucket => format!(
+        "https://bitbucket.org/{}/get/{}.tar.gz",
+        self.repository_path(),
+        committish
+      ),
+      GitHost::Gist => format!(
+        "https://codeload.github.com/gist/{}/tar.gz/{}",
+        self.project, committish
+      ),
+    }
+  }
+
+  /// The canonical `repository` field, whose url is the [https_url][HostedGitInfo::https_url].
+  pub fn to_repository(&self) -> PackageRepositoryRecord {
+    PackageRepositoryRecord {
+      r#type: "git".to_owned(),
+      url: self.https_url(),
+      directory: self.directory.clone(),
+    }
+  }
+}
+
+/// Remove the `.git` suffix of a repository name, e.g. `cli.git` becomes `cli`.
+fn strip_git(project: &str) -> String {
+  project.strip_suffix(".git").unwrap_or(project).to_owned()
+}
+
+fn domain_of(host: GitHost) -> &'static str {
+  match host {
+    GitHost::GitHub => "github.com",
+    GitHost::GitLab => "gitlab.com",
+    GitHost::Bitbucket => "bitbucket.org",
+    GitHost::Gist => "gist.github.com",
+  }
+}
+
+impl PackageRepository {
+  /// Parse the repository into a [HostedGitInfo] with the `directory` of the record form, it returns [None] when the repository isn't on a known host.
+  pub fn hosted_git_info(&self) -> Option<HostedGitInfo> {
+    match self {
+      Self::Url(url) => HostedGitInfo::parse(url),
+      Self::Record(record) => {
+        let info = HostedGitInfo::parse(&record.url)?;
+        Some(match &record.directory {
+          Some(directory) => info.with_directory(directory.as_str()),
+          None => info,
+        })
+      }
+    }
+  }
+}
+
+impl PackageJson {
+  /// See [PackageRepository::hosted_git_info].
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let json: PackageJson = serde_json::from_str(r#"{
+  ///   "name": "libnpmpublish",
+  ///   "version": "1.0.0",
+  ///   "repository": {
+  ///     "type": "git",
+  ///     "url": "https://github.com/npm/cli.git",
+  ///     "directory": "workspaces/libnpmpublish"
+  ///   }
+  /// }"#).unwrap();
+  /// let info = json.hosted_git_info().unwrap();
+  /// assert_eq!(info.browse_url(), "https://github.com/npm/cli/tree/HEAD/workspaces/libnpmpublish");
+  /// ```
+  pub fn hosted_git_info(&self) -> Option<HostedGitInfo> {
+    self.repository.as_ref()?.hosted_git_info()
+  }
+}
+
+#[test]
+fn test_parse() {
+  let info = |host, user: Option<&str>, project: &str, committish: Option<&str>| HostedGitInfo {
+    host,
+    user: user.map(|user| user.to_owned()),
+    project: project.to_owned(),
+    committish: committish.map(|committish| committish.to_owned()),
+    directory: None,
+  };
+
+  for (url, expected) in [
+    ("npm/cli", info(GitHost::GitHub, Some("npm"), "cli", None)),
+    (
+      "npm/cli.git",
+      info(GitHost::GitHub, Some("npm"), "cli", None),
+    ),
+    (
+      "github:npm/cli.git",
+      info(GitHost::GitHub, Some("npm"), "cli", None),
+    ),
+    (
+      "github:npm/cli#v1.0.0",
+      info(GitHost::GitHub, Some("npm"), "cli", Some("v1.0.0")),
+    ),
+    (
+      "gitlab:user/repo",
+      info(GitHost::GitLab, Some("user"), "repo", None),
+    ),
+    (
+      "bitbucket:user/repo",
+      info(GitHost::Bitbucket, Some("user"), "repo", None),
+    ),
+    ("gist:abc123", info(GitHost::Gist, None, "abc123", None)),
+    (
+      "git+https://github.com/npm/cli.git",
+      info(GitHost::GitHub, Some("npm"), "cli", None),
+    ),
+    (
+      "https://www.github.com/npm/cli/tree/release/v10",
+      info(GitHost::GitHub, Some("npm"), "cli", Some("release/v10")),
+    ),
+    (
+      "git://github.com/npm/cli.git#main",
+      info(GitHost::GitHub, Some("npm"), "cli", Some("main")),
+    ),
+    (
+      "git+ssh://git@github.com/npm/cli.git",
+      info(GitHost::GitHub, Some("npm"), "cli", None),
+    ),
+    (
+      "git@github.com:npm/cli.git",
+      info(GitHost::GitHub, Some("npm"), "cli", None),
+    ),
+    (
+      "https://gitlab.com/group/subgroup/repo.git",
+      info(GitHost::GitLab, Some("group/subgroup"), "repo", None),
+    ),
+    (
+      "https://bitbucket.org/user/repo",
+      info(GitHost::Bitbucket, Some("user"), "repo", None),
+    ),
+    (
+      "https://gist.github.com/user/abc123",
+      info(GitHost::Gist, Some("user"), "abc123", None),
+    ),
+  ] {
+    assert_eq!(HostedGitInfo::parse(url), Some(expected), "{:?}", url);
+  }
+
+  for url in [
+    "https://example.com/npm/cli.git",
+    "https://github.com/npm",
+    "https://github.com/npm/cli/issues",
+    "https://gitlab.com/group/repo/-/tree/main",
+    "file:../cli",
+    "latest",
+  ] {
+    assert_eq!(HostedGitInfo::parse(url), None, "{:?}", url);
+  }
+}
+
+#[test]
+fn test_urls() {
+  for (url, https, browse, bugs, tarball) in [
+    (
+      "github:npm/cli",
+      "git+https://github.com/npm/cli.git",
+      "https://github.com/npm/cli",
+      "https://github.com/npm/cli/issues",
+      "https://codeload.github.com/npm/cli/tar.gz/HEAD",
+    ),
+    (
+      "npm/cli.git",
+      "git+https://github.com/npm/cli.git",
+      "https://github.com/npm/cli",
+      "https://github.com/npm/cli/issues",
+      "https://codeload.github.com/npm/cli/tar.gz/HEAD",
+    ),
+    (
+      "github:npm/cli.git#v1",
+      "git+https://github.com/npm/cli.git#v1",
+      "https://github.com/npm/cli/tree/v1",
+      "https://github.com/npm/cli/issues",
+      "https://codeload.github.com/npm/cli/tar.gz/v1",
+    ),
+    (
+      "gitlab:group/subgroup/repo#v1",
+      "git+https://gitlab.com/group/subgroup/repo.git#v1",
+      "https://gitlab.com/group/subgroup/repo/tree/v1",
+      "https://gitlab.com/group/subgroup/repo/issues",
+      "https://gitlab.com/group/subgroup/repo/repository/archive.tar.gz?ref=v1",
+    ),
+    (
+      "bitbucket:user/repo#main",
+      "git+https://bitbucket.org/user/repo.git#main",
+      "https://bitbucket.org/user/repo/src/main",
+      "https://bitbucket.org/user/repo/issues",
+      "https://bitbucket.org/user/repo/get/main.tar.gz",
+    ),
+    (
+      "gist:user/abc123",
+      "git+https://gist.github.com/abc123.git",
+      "https://gist.github.com/abc123",
+      "https://gist.github.com/abc123",
+      "https://codeload.github.com/gist/abc123/tar.gz/HEAD",
+    ),
+  ] {
+    let info = HostedGitInfo::parse(url).unwrap();
+    assert_eq!(info.https_url(), https);
+    assert_eq!(info.browse_url(), browse);
+    assert_eq!(info.bugs_url(), bugs);
+    assert_eq!(info.tarball_url(), tarball);
+  }
+
+  let info = HostedGitInfo::parse("git@github.com:npm/cli.git").unwrap();
+  assert_eq!(info.shortcut(), "github:npm/cli");
+  assert_eq!(info.ssh_url(), "git+ssh://git@github.com/npm/cli.git");
+  assert_eq!(info.docs_url(), "https://github.com/npm/cli#readme");
+}
+
+#[test]
+fn test_directory() {
+  let repository = PackageRepository::Record(PackageRepositoryRecord {
+    r#type: "git".to_owned(),
+    url: "bitbucket:user/repo".to_owned(),
+    directory: Some("/packages/a/".to_owned()),
+  });
+  let info = repository.hosted_git_info().unwrap();
+  assert_eq!(info.directory.as_deref(), Some("packages/a"));
+  assert_eq!(
+    info.browse_url(),
+    "https://bitbucket.org/user/repo/src/HEAD/packages/a"
+  );
+  let record = info.to_repository();
+  assert_eq!(record.url, "git+https://bitbucket.org/user/repo.git");
+  assert_eq!(record.directory.as_deref(), Some("packages/a"));
+
+  let repository = PackageRepository::Url("npm/cli".to_owned());
+  assert_eq!(repository.hosted_git_info().unwrap().directory, None);
+}
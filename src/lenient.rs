@@ -0,0 +1,238 @@
+//! The lenient mode of parsing `package.json`, which recovers the wrongly-typed known fields instead of failing.
+
+use crate::error::{Error, Result};
+use crate::fs;
+use crate::{PackageJson, ParseError};
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+use serde_path_to_error::Segment;
+
+/// The fields which are filled with an empty string when they are missing.
+const REQUIRED_FIELDS: [&str; 2] = ["name", "version"];
+
+/// The fields of a plain string, a number or boolean of them is converted into a string.
+const STRING_FIELDS: [&str; 9] = [
+  "name",
+  "version",
+  "description",
+  "homepage",
+  "main",
+  "browser",
+  "type",
+  "types",
+  "typings",
+];
+
+impl PackageJson {
+  /// Parse `contents` leniently, the known fields with a wrong type are recovered as the following steps, and a warning is reported for each of them.
+  ///
+  /// 1. Coerce the obviously recoverable shapes, e.g. a single string or object where an array is expected (`"files": "dist"`), a comma-separated string of `keywords`, a number or boolean where a string is expected (`"version": 1`), a `"true"` or `"false"` string where a boolean is expected, or the invalid entries of an object or array (`"scripts": { "test": null }`).
+  /// 2. Otherwise, move the field into [unknowns][PackageJson::unknowns], so that it's kept as it is when the `PackageJson` is written back.
+  ///
+  /// The missing `name` and `version` are filled with an empty string. It only fails when `contents` isn't a JSON object.
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let (json, warnings) = PackageJson::parse_lenient(r#"{
+  ///   "name": "test",
+  ///   "version": "1.0.0",
+  ///   "keywords": "foo, bar",
+  ///   "bin": 42
+  /// }"#).unwrap();
+  /// assert_eq!(json.keywords, Some(vec!["foo".to_owned(), "bar".to_owned()]));
+  /// assert_eq!(json.bin, None);
+  /// assert_eq!(json.unknowns["bin"], 42);
+  /// assert_eq!(warnings.len(), 2);
+  /// ```
+  pub fn parse_lenient(contents: &str) -> Result<(Self, Vec<String>)> {
+    let Value::Object(mut object) = fs::parse_json::<Value>(contents)? else {
+      return Err(Error::Parse(ParseError::at(
+        contents,
+        0,
+        "expected a JSON object",
+      )));
+    };
+    let mut warnings = Vec::new();
+    for field in REQUIRED_FIELDS {
+      if !object.contains_key(field) {
+        object.insert(field.to_owned(), Value::String(String::new()));
+        warnings.push(format!("The \"{}\" field is missing.", field));
+      }
+    }
+
+    let mut invalid = IndexMap::new();
+    let mut json = loop {
+      let (field, message) = match deserialize(&object) {
+        Ok(json) => break json,
+        Err((Some(field), message)) => (field, message),
+        Err((None, message)) => return Err(Error::Validation(message)),
+      };
+      let value = object[&field].clone();
+      let coerced = coerce(&field, &value).into_iter().find(|(candidate, _)| {
+        object.insert(field.clone(), candidate.clone());
+        !matches!(deserialize(&object), Err((Some(failed), _)) if failed == field)
+      });
+      match coerced {
+        Some((_, reason)) => warnings.push(format!("The \"{}\" field {}.", field, reason)),
+        None => {
+          object.remove(&field);
+          warnings.push(format!(
+            "The \"{}\" field is invalid and kept in unknowns: {}.",
+            field, message
+          ));
+          invalid.insert(field, value);
+        }
+      }
+    };
+    json.unknowns.extend(invalid);
+    Ok((json, warnings))
+  }
+}
+
+/// Deserialize `object`, return the top-level field and the message of the failure.
+fn deserialize(
+  object: &Map<String, Value>,
+) -> std::result::Result<PackageJson, (Option<String>, String)> {
+  let value = Value::Object(object.clone());
+  serde_path_to_error::deserialize(&value).map_err(|error| {
+    let field = match error.path().iter().next() {
+      Some(Segment::Map { key }) => Some(key.to_owned()),
+      _ => None,
+    };
+    (field, error.into_inner().to_string())
+  })
+}
+
+/// The candidates of recovering `value` of `field`, and the reasons of them.
+fn coerce(field: &str, value: &Value) -> Vec<(Value, &'static str)> {
+  let mut candidates = Vec::new();
+  match value {
+    Value::String(string) => {
+      if field == "keywords" {
+        let keywords = string
+          .split(|char: char| char == ',' || char.is_whitespace())
+          .filter(|keyword| !keyword.is_empty())
+          .map(|keyword| Value::String(keyword.to_owned()))
+          .collect();
+        candidates.push((Value::Array(keywords), "is split into an array"));
+      }
+      if let Ok(boolean) = string.parse::<bool>() {
+        candidates.push((Value::Bool(boolean), "is converted into a boolean"));
+      }
+      candidates.push((
+        Value::Array(vec![value.clone()]),
+        "is wrapped into an array",
+      ));
+    }
+    Value::Number(_) | Value::Bool(_) if STRING_FIELDS.contains(&field) => {
+      candidates.push((
+        Value::String(value.to_string()),
+        "is converted into a string",
+      ));
+    }
+    Value::Object(object) => {
+      candidates.push((
+        Value::Array(vec![value.clone()]),
+        "is wrapped into an array",
+      ));
+      let entries = object
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_owned(), stringify(value)?)))
+        .collect();
+      candidates.push((Value::Object(entries), "has invalid entries removed"));
+    }
+    Value::Array(items) => {
+      let items = items.iter().filter_map(stringify).collect();
+      candidates.push((Value::Array(items), "has invalid items removed"));
+    }
+    _ => {}
+  }
+  candidates
+}
+
+/// Convert a scalar into a string, the other values are invalid.
+fn stringify(value: &Value) -> Option<Value> {
+  match value {
+    Value::String(_) => Some(value.clone()),
+    Value::Number(_) | Value::Bool(_) => Some(Value::String(value.to_string())),
+    _ => None,
+  }
+}
+
+#[test]
+fn test_parse_lenient() {
+  use crate::{PackageFunding, PackagePeople};
+
+  let (json, warnings) = PackageJson::parse_lenient(
+    r#"{
+      "version": 1,
+      "description": "test",
+      "keywords": "foo bar,baz",
+      "files": "dist",
+      "contributors": { "name": "Barney Rubble" },
+      "funding": "https://example.com/donate",
+      "bin": 42,
+      "scripts": { "test": "jest", "lint": null, "build": 1 },
+      "private": "true",
+      "engines": ["node >= 0.6"],
+      "os": ["linux", { "name": "darwin" }],
+      "custom": 42
+    }"#,
+  )
+  .unwrap();
+
+  assert_eq!(json.name, "");
+  assert_eq!(json.version, "1");
+  assert_eq!(json.description.as_deref(), Some("test"));
+  assert_eq!(
+    json.keywords,
+    Some(vec!["foo".to_owned(), "bar".to_owned(), "baz".to_owned()])
+  );
+  assert_eq!(json.files, Some(vec!["dist".to_owned()]));
+  assert!(matches!(
+    json.contributors.as_deref(),
+    Some([PackagePeople::Record(record)]) if record.name == "Barney Rubble"
+  ));
+  assert!(matches!(
+    json.funding.as_deref(),
+    Some([PackageFunding::Url(_)])
+  ));
+  assert!(json.bin.is_none());
+  assert_eq!(
+    serde_json::to_value(&json.scripts).unwrap(),
+    serde_json::json!({ "test": "jest", "build": "1" })
+  );
+  assert!(json.private);
+  assert!(json.engines.is_none());
+  assert_eq!(json.os, Some(vec!["linux".to_owned()]));
+  assert_eq!(
+    serde_json::to_value(&json.unknowns).unwrap(),
+    serde_json::json!({ "custom": 42, "bin": 42, "engines": ["node >= 0.6"] })
+  );
+
+  assert_eq!(
+    warnings,
+    vec![
+      "The \"name\" field is missing.",
+      "The \"version\" field is converted into a string.",
+      "The \"keywords\" field is split into an array.",
+      "The \"files\" field is wrapped into an array.",
+      "The \"contributors\" field is wrapped into an array.",
+      "The \"funding\" field is wrapped into an array.",
+      "The \"bin\" field is invalid and kept in unknowns: data did not match any variant of untagged enum PackageBin.",
+      "The \"scripts\" field has invalid entries removed.",
+      "The \"private\" field is converted into a boolean.",
+      "The \"engines\" field is invalid and kept in unknowns: invalid type: sequence, expected a map.",
+      "The \"os\" field has invalid items removed.",
+    ]
+  );
+
+  assert!(matches!(
+    PackageJson::parse_lenient("[]"),
+    Err(Error::Parse(_))
+  ));
+  assert!(matches!(
+    PackageJson::parse_lenient("{"),
+    Err(Error::Parse(_))
+  ));
+}
@@ -7,7 +7,7 @@
 //! use package_json::PackageJsonManager;
 //! use std::path::Path;
 //!
-//! # use anyhow::Result;
+//! # use package_json::Result;
 //! # fn main() -> Result<()> {
 //! let mut manager = PackageJsonManager::new();
 //! // based on the current working directory
@@ -61,11 +61,60 @@
 //! # }
 //! ```
 //!
+//! Enable [`preserve_format`][WriteOptions::preserve_format] to only rewrite the fields we changed, the rest of the file is kept untouched.
+//! ```no_run
+//! use package_json::{PackageJsonManager, WriteOptionsBuilder};
+//!
+//! # fn main() {
+//! let mut manager = PackageJsonManager::with_write_options(
+//!   WriteOptionsBuilder::default().preserve_format(true).build().unwrap()
+//! );
+//! if manager.locate_closest().is_ok() {
+//!   if let Ok(json) = manager.read_mut() {
+//!     json.version = "1.0.1".to_string();
+//!   }
+//!   assert!(manager.write().is_ok());
+//! }
+//! # }
+//! ```
+//!
 
+mod dependency_spec;
+mod error;
 mod fs;
+mod hosted_git;
+mod lenient;
+mod license;
+mod lint;
 mod manager;
+mod name;
+mod normalize;
+mod people;
+mod resolve;
 mod schema;
+mod semver;
+mod workspace;
 
-pub use crate::fs::write_options::{WriteOptions, WriteOptionsBuilder};
+pub use crate::dependency_spec::{DependencySpec, GitCommittish, GitHost};
+pub use crate::error::{Error, Result};
+pub use crate::fs::parse_error::ParseError;
+pub use crate::fs::write_options::{
+  Indent, LineEnding, PeopleFormat, WriteOptions, WriteOptionsBuilder,
+};
+pub use crate::hosted_git::HostedGitInfo;
+pub use crate::license::correct::correct_license;
+pub use crate::license::expression::LicenseExpression;
+pub use crate::license::{validate_license, LicenseValidation};
+pub use crate::lint::rules::{
+  DuplicateDependencies, LicenseRequired, MainExists, NoWildcardRange, RepositoryRequired,
+};
+pub use crate::lint::{json_pointer, Diagnostic, LintContext, Linter, Rule, Severity, Violation};
 pub use crate::manager::{PackageJsonManager, PACKAGE_JSON_FILENAME};
+pub use crate::name::{split_package_name, validate_package_name, NameValidation};
+pub use crate::resolve::ResolvedImport;
 pub use crate::schema::*;
+pub use crate::semver::range::{Comparator, Operator, Range};
+pub use crate::semver::version::{Identifier, ReleaseType, Version};
+pub use crate::workspace::graph::{DependencyEdge, DependencyGraph, DependencyKind};
+pub use crate::workspace::pnpm::{PnpmWorkspace, PNPM_WORKSPACE_FILENAME};
+pub use crate::workspace::{Workspace, WorkspaceMember, WorkspaceProblem};
@@ -0,0 +1,306 @@
+//! SPDX license expressions of the [license](https://docs.npmjs.com/cli/v10/configuring-npm/package-json#license) field, equivalent to [validate-npm-package-license](https://github.com/kemitchell/validate-npm-package-license.js).
+
+use crate::{PackageJson, PackageLicense};
+use expression::{is_license_ref, LicenseExpression};
+use list::{DEPRECATED_EXCEPTION_IDS, DEPRECATED_LICENSE_IDS, EXCEPTION_IDS, LICENSE_IDS};
+
+pub(crate) mod correct;
+pub(crate) mod expression;
+mod list;
+
+/// The result of [validate_license].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LicenseValidation {
+  /// Whether the license can be used by a new package, i.e. there are neither errors nor warnings.
+  pub valid_for_new_packages: bool,
+  /// Whether the license is valid for an existing package, i.e. there are no errors.
+  pub valid_for_old_packages: bool,
+  pub errors: Vec<String>,
+  pub warnings: Vec<String>,
+  /// The parsed expression when the license is an SPDX license expression.
+  pub expression: Option<LicenseExpression>,
+  /// The file name of `SEE LICENSE IN <filename>`.
+  pub file: Option<String>,
+  /// Whether the license is `UNLICENSED`, i.e. the package isn't licensed for use by others.
+  pub unlicensed: bool,
+  /// A valid license expression which the license is probably meant to be, e.g. `Apache-2.0` for `Apache 2`.
+  pub suggestion: Option<String>,
+}
+
+/// Validate a license against the embedded SPDX license list. Besides SPDX license expressions, `UNLICENSED` and `SEE LICENSE IN <filename>` are valid as npm allows.
+/// ```
+/// use package_json::validate_license;
+///
+/// assert!(validate_license("MIT OR (Apache-2.0 AND BSD-3-Clause)").valid_for_new_packages);
+/// assert!(validate_license("SEE LICENSE IN LICENSE.md").valid_for_new_packages);
+///
+/// let validation = validate_license("Apache 2");
+/// assert!(!validation.valid_for_old_packages);
+/// assert_eq!(validation.suggestion.as_deref(), Some("Apache-2.0"));
+/// ```
+pub fn validate_license(license: &str) -> LicenseValidation {
+  let mut validation = LicenseValidation::default();
+
+  if matches!(license, "UNLICENSED" | "UNLICENCED") {
+    validation.unlicensed = true;
+  } else if let Some(file) = ["SEE LICENSE IN ", "SEE LICENCE IN "]
+    .iter()
+    .find_map(|prefix| license.strip_prefix(prefix))
+    .filter(|file| !file.trim().is_empty())
+  {
+    validation.file = Some(file.trim().to_owned());
+  } else {
+    match LicenseExpression::parse(license) {
+      Ok(expression) => {
+        for id in expression.licenses() {
+          if DEPRECATED_LICENSE_IDS.binary_search(&id).is_ok() {
+            validation.warnings.push(format!(
+              "\"{}\" is a deprecated SPDX license identifier",
+              id
+            ));
+          } else if LICENSE_IDS.binary_search(&id).is_err() && !is_license_ref(id) {
+            validation.errors.push(format!(
+              "\"{}\" is not a known SPDX license identifier",
+              id
+            ));
+          }
+        }
+        for id in expression.exceptions() {
+          if DEPRECATED_EXCEPTION_IDS.binary_search(&id).is_ok() {
+            validation.warnings.push(format!(
+              "\"{}\" is a deprecated SPDX license exception identifier",
+              id
+            ));
+          } else if EXCEPTION_IDS.binary_search(&id).is_err() {
+            validation.errors.push(format!(
+              "\"{}\" is not a known SPDX license exception identifier",
+              id
+            ));
+          }
+        }
+        validation.expression = Some(expression);
+      }
+      Err(_) => validation.errors.push(
+        "license should be a valid SPDX license expression, \"UNLICENSED\", or \"SEE LICENSE IN <filename>\"".to_owned(),
+      ),
+    }
+    if !validation.errors.is_empty() || !validation.warnings.is_empty() {
+      validation.suggestion =
+        correct::correct_license(license).filter(|suggestion| suggestion != license);
+    }
+  }
+
+  validation.valid_for_old_packages = validation.errors.is_empty();
+  validation.valid_for_new_packages =
+    validation.errors.is_empty() && validation.warnings.is_empty();
+  validation
+}
+
+/// Whether `expression` is an SPDX license expression whose identifiers are all in the SPDX license list, including the deprecated ones.
+pub(crate) fn is_known_expression(expression: &str) -> bool {
+  LicenseExpression::parse(expression).is_ok_and(|expression| {
+    expression.licenses().into_iter().all(|id| {
+      LICENSE_IDS.binary_search(&id).is_ok()
+        || DEPRECATED_LICENSE_IDS.binary_search(&id).is_ok()
+        || is_license_ref(id)
+    }) && expression.exceptions().into_iter().all(|id| {
+      EXCEPTION_IDS.binary_search(&id).is_ok()
+        || DEPRECATED_EXCEPTION_IDS.binary_search(&id).is_ok()
+    })
+  })
+}
+
+impl PackageLicense {
+  /// The license expression, which is the `type` of the legacy object form.
+  pub fn as_str(&self) -> &str {
+    match self {
+      Self::Expression(expression) => expression,
+      Self::Record(record) => &record.r#type,
+    }
+  }
+}
+
+impl PackageJson {
+  /// Validate the license of the package, see [validate_license]. The legacy [licenses][PackageJson::licenses] field is validated as an `OR` expression of its types when there is no [license][PackageJson::license] field. It returns [None] when there is no license at all.
+  pub fn validate_license(&self) -> Option<LicenseValidation> {
+    self.raw_license().as_deref().map(validate_license)
+  }
+
+  /// Convert the [license][PackageJson::license] or the legacy [licenses][PackageJson::licenses] field into a modern SPDX license expression, invalid and deprecated identifiers are corrected when possible, e.g. `Apache-2.0` for `{ "type": "Apache 2" }` and `GPL-2.0-only` for `GPL-2.0`.
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let json: PackageJson = serde_json::from_str(r#"{
+  ///   "name": "legacy",
+  ///   "version": "1.0.0",
+  ///   "licenses": [
+  ///     { "type": "MIT", "url": "https://opensource.org/licenses/MIT" },
+  ///     { "type": "Apache 2", "url": "https://opensource.org/licenses/Apache-2.0" }
+  ///   ]
+  /// }"#).unwrap();
+  /// assert_eq!(json.license_expression().as_deref(), Some("MIT OR Apache-2.0"));
+  /// ```
+  pub fn license_expression(&self) -> Option<String> {
+    let correct = |license: &str| {
+      let validation = validate_license(license);
+      match validation.suggestion {
+        Some(suggestion) if !validation.valid_for_new_packages => suggestion,
+        _ => license.to_owned(),
+      }
+    };
+    match &self.license {
+      Some(license) => Some(correct(license.as_str())),
+      None => {
+        let licenses = self
+          .licenses
+          .as_ref()
+          .filter(|licenses| !licenses.is_empty())?;
+        // `OR` has the lowest precedence, so the expressions never need parentheses.
+        Some(
+          licenses
+            .iter()
+            .map(|license| correct(&license.r#type))
+            .collect::<Vec<_>>()
+            .join(" OR "),
+        )
+      }
+    }
+  }
+
+  /// Replace the legacy license forms with the [license_expression][PackageJson::license_expression], i.e. [license][PackageJson::license] becomes an SPDX expression string and [licenses][PackageJson::licenses] is removed.
+  pub fn normalize_license(&mut self) {
+    if let Some(expression) = self.license_expression() {
+      self.license = Some(PackageLicense::Expression(expression));
+    }
+    self.licenses = None;
+  }
+
+  fn raw_license(&self) -> Option<String> {
+    match &self.license {
+      Some(license) => Some(license.as_str().to_owned()),
+      None => self
+        .licenses
+        .as_ref()
+        .filter(|licenses| !licenses.is_empty())
+        .map(|licenses| {
+          licenses
+            .iter()
+            .map(|license| license.r#type.as_str())
+            .collect::<Vec<_>>()
+            .join(" OR ")
+        }),
+    }
+  }
+}
+
+#[test]
+fn test_validate_license() {
+  for (license, new, old) in [
+    ("MIT", true, true),
+    ("MIT OR (Apache-2.0 AND BSD-3-Clause)", true, true),
+    ("GPL-2.0-or-later WITH Classpath-exception-2.0", true, true),
+    ("LicenseRef-Proprietary", true, true),
+    ("UNLICENSED", true, true),
+    ("SEE LICENSE IN LICENSE.md", true, true),
+    ("GPL-2.0", false, true),
+    ("MIT AND Nokia-Qt-exception-1.1", false, false),
+    ("MIT WITH Nokia-Qt-exception-1.1", false, true),
+    ("MIT WITH Unknown-exception", false, false),
+    ("Apache 2", false, false),
+    ("Unknown-License", false, false),
+    ("SEE LICENSE IN ", false, false),
+    ("", false, false),
+  ] {
+    let validation = validate_license(license);
+    assert_eq!(
+      (
+        validation.valid_for_new_packages,
+        validation.valid_for_old_packages
+      ),
+      (new, old),
+      "validate {:?}: {:?}",
+      license,
+      validation
+    );
+  }
+
+  let validation = validate_license("GPL-2.0");
+  assert_eq!(
+    validation.warnings,
+    vec!["\"GPL-2.0\" is a deprecated SPDX license identifier"]
+  );
+  assert_eq!(validation.suggestion.as_deref(), Some("GPL-2.0-only"));
+
+  let validation = validate_license("Apache-2");
+  assert_eq!(
+    validation.errors,
+    vec!["\"Apache-2\" is not a known SPDX license identifier"]
+  );
+  assert_eq!(validation.suggestion.as_deref(), Some("Apache-2.0"));
+
+  assert_eq!(
+    validate_license("SEE LICENSE IN LICENSE.md")
+      .file
+      .as_deref(),
+    Some("LICENSE.md")
+  );
+  assert!(validate_license("UNLICENSED").unlicensed);
+}
+
+#[test]
+fn test_normalize_license() {
+  use crate::PackageLicenseRecord;
+
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "legacy",
+      "version": "1.0.0",
+      "license": { "type": "MIT", "url": "https://opensource.org/licenses/MIT" }
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(
+    json.license,
+    Some(PackageLicense::Record(PackageLicenseRecord {
+      r#type: "MIT".to_owned(),
+      url: Some("https://opensource.org/licenses/MIT".to_owned()),
+    }))
+  );
+  assert!(json.validate_license().unwrap().valid_for_new_packages);
+  json.normalize_license();
+  assert_eq!(
+    json.license,
+    Some(PackageLicense::Expression("MIT".to_owned()))
+  );
+
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "legacy",
+      "version": "1.0.0",
+      "licenses": [
+        { "type": "GPL-2.0" },
+        { "type": "Apache 2" }
+      ]
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(json.licenses.as_ref().unwrap().len(), 2);
+  assert!(!json.validate_license().unwrap().valid_for_old_packages);
+  json.normalize_license();
+  assert_eq!(
+    json.license,
+    Some(PackageLicense::Expression(
+      "GPL-2.0-only OR Apache-2.0".to_owned()
+    ))
+  );
+  assert_eq!(json.licenses, None);
+  assert_eq!(
+    serde_json::to_value(&json).unwrap()["license"],
+    "GPL-2.0-only OR Apache-2.0"
+  );
+
+  let mut json = PackageJson::default();
+  json.normalize_license();
+  assert_eq!(json.license, None);
+  assert_eq!(json.validate_license(), None);
+}
@@ -0,0 +1,425 @@
+//! Corrections of invalid license expressions, ported from [spdx-correct](https://github.com/jslicense/spdx-correct.js).
+
+use super::is_known_expression;
+use super::list::LICENSE_IDS;
+
+/// Common transpositions of license identifier acronyms.
+const TRANSPOSITIONS: [(&str, &str); 35] = [
+  ("APGL", "AGPL"),
+  ("Gpl", "GPL"),
+  ("GLP", "GPL"),
+  ("APL", "Apache"),
+  ("ISD", "ISC"),
+  ("GLP", "GPL"),
+  ("IST", "ISC"),
+  ("Claude", "Clause"),
+  (" or later", "+"),
+  (" International", ""),
+  ("GNU", "GPL"),
+  ("GUN", "GPL"),
+  ("+", ""),
+  ("GNU GPL", "GPL"),
+  ("GNU LGPL", "LGPL"),
+  ("GNU/GPL", "GPL"),
+  ("GNU GLP", "GPL"),
+  ("GNU LESSER GENERAL PUBLIC LICENSE", "LGPL"),
+  ("GNU Lesser General Public License", "LGPL"),
+  ("GNU LESSER GENERAL PUBLIC LICENSE", "LGPL-2.1"),
+  ("GNU Lesser General Public License", "LGPL-2.1"),
+  ("LESSER GENERAL PUBLIC LICENSE", "LGPL"),
+  ("Lesser General Public License", "LGPL"),
+  ("LESSER GENERAL PUBLIC LICENSE", "LGPL-2.1"),
+  ("Lesser General Public License", "LGPL-2.1"),
+  ("GNU General Public License", "GPL"),
+  ("Gnu public license", "GPL"),
+  ("GNU Public License", "GPL"),
+  ("GNU GENERAL PUBLIC LICENSE", "GPL"),
+  ("MTI", "MIT"),
+  ("Mozilla Public License", "MPL"),
+  ("Universal Permissive License", "UPL"),
+  ("WTH", "WTF"),
+  ("WTFGPL", "WTFPL"),
+  ("-License", ""),
+];
+
+/// If all else fails, guess that strings containing certain substrings meant to identify certain licenses.
+const LAST_RESORTS: [(&str, &str); 29] = [
+  ("UNLI", "Unlicense"),
+  ("WTF", "WTFPL"),
+  ("2 CLAUSE", "BSD-2-Clause"),
+  ("2-CLAUSE", "BSD-2-Clause"),
+  ("3 CLAUSE", "BSD-3-Clause"),
+  ("3-CLAUSE", "BSD-3-Clause"),
+  ("AFFERO", "AGPL-3.0-or-later"),
+  ("AGPL", "AGPL-3.0-or-later"),
+  ("APACHE", "Apache-2.0"),
+  ("ARTISTIC", "Artistic-2.0"),
+  ("Affero", "AGPL-3.0-or-later"),
+  ("BEER", "Beerware"),
+  ("BOOST", "BSL-1.0"),
+  ("BSD", "BSD-2-Clause"),
+  ("CDDL", "CDDL-1.1"),
+  ("ECLIPSE", "EPL-1.0"),
+  ("FUCK", "WTFPL"),
+  ("GNU", "GPL-3.0-or-later"),
+  ("LGPL", "LGPL-3.0-or-later"),
+  ("GPLV1", "GPL-1.0-only"),
+  ("GPL-1", "GPL-1.0-only"),
+  ("GPLV2", "GPL-2.0-only"),
+  ("GPL-2", "GPL-2.0-only"),
+  ("GPL", "GPL-3.0-or-later"),
+  ("MIT +NO-FALSE-ATTRIBS", "MITNFA"),
+  ("MIT", "MIT"),
+  ("MPL", "MPL-2.0"),
+  ("X11", "X11"),
+  ("ZLIB", "Zlib"),
+];
+
+/// Suggest a valid SPDX license expression for an invalid one, e.g. `Apache-2.0` for `Apache 2`. It returns [None] when there is no reasonable guess.
+/// ```
+/// use package_json::correct_license;
+///
+/// assert_eq!(correct_license("Apache 2").as_deref(), Some("Apache-2.0"));
+/// assert_eq!(correct_license("mit").as_deref(), Some("MIT"));
+/// assert_eq!(correct_license("GPLv2").as_deref(), Some("GPL-2.0-only"));
+/// assert_eq!(correct_license("no idea"), None);
+/// ```
+pub fn correct_license(identifier: &str) -> Option<String> {
+  let identifier = identifier.trim();
+  if identifier.is_empty() {
+    return None;
+  }
+  if is_known_expression(identifier) {
+    return Some(upgrade_gpl(identifier));
+  }
+  let no_plus = identifier.strip_suffix('+').unwrap_or(identifier).trim();
+  if is_known_expression(no_plus) {
+    return Some(upgrade_gpl(no_plus));
+  }
+
+  valid_transformation(identifier)
+    .or_else(|| {
+      any_correction(identifier, |corrected| {
+        if is_known_expression(corrected) {
+          Some(corrected.to_owned())
+        } else {
+          valid_transformation(corrected)
+        }
+      })
+    })
+    .or_else(|| valid_last_resort(identifier))
+    .or_else(|| any_correction(identifier, valid_last_resort))
+    .map(|corrected| upgrade_gpl(&corrected))
+}
+
+/// Simple corrections to nearly valid identifiers.
+fn transforms(argument: &str) -> Vec<String> {
+  let mut capitalized = argument.chars();
+  let capitalized = capitalized
+    .next()
+    .map(|first| first.to_uppercase().chain(capitalized).collect())
+    .unwrap_or_default();
+  let versions = ["V.", "v.", "V", "v", "Version", "version"];
+  let attribution = argument
+    .replacen("Attribution", "BY", 1)
+    .replacen("NonCommercial", "NC", 1)
+    .replacen("NoDerivatives", "ND", 1);
+  let attribution = replace_digit(&attribution, &[""], " ", "-", "")
+    .replacen(" International", "", 1)
+    .replacen("International", "", 1);
+
+  vec![
+    // e.g. 'mit'
+    argument.to_uppercase(),
+    // e.g. 'MIT '
+    argument.trim().to_owned(),
+    // e.g. 'M.I.T.'
+    argument.replace('.', ""),
+    // e.g. 'Apache- 2.0'
+    argument.split_whitespace().collect(),
+    // e.g. 'CC BY 4.0'
+    argument.split_whitespace().collect::<Vec<_>>().join("-"),
+    // e.g. 'LGPLv2.1'
+    argument.replacen('v', "-", 1),
+    // e.g. 'Apache 2.0'
+    replace_digit(argument, &[""], ", ", "-", ""),
+    // e.g. 'GPL 2'
+    replace_digit(argument, &[""], ", ", "-", ".0"),
+    // e.g. 'Apache Version 2.0'
+    replace_digit(argument, &versions, ", ", "-", ""),
+    // e.g. 'Apache Version 2'
+    replace_digit(argument, &versions, ", ", "-", ".0"),
+    // e.g. 'ZLIB'
+    capitalized,
+    // e.g. 'MPL/2.0'
+    argument.replacen('/', "-", 1),
+    // e.g. 'Apache 2'
+    {
+      let replaced = replace_digit(argument, &["V"], " ", "-", "");
+      match replaced.chars().last() {
+        Some(last) if last.is_ascii_digit() => format!("{}.0", replaced),
+        _ => replaced,
+      }
+    },
+    // e.g. 'GPL-2.0', 'GPL-3.0'
+    if argument.contains("3.0") {
+      format!("{}-or-later", argument)
+    } else {
+      format!("{}-only", argument)
+    },
+    // e.g. 'GPL-2.0-'
+    format!("{}only", argument),
+    // e.g. 'GPL2'
+    match argument.chars().last() {
+      Some(last) if last.is_ascii_digit() => {
+        format!("{}-{}.0", &argument[..argument.len() - 1], last)
+      }
+      _ => argument.to_owned(),
+    },
+    // e.g. 'BSD 3'
+    match argument.chars().last() {
+      Some(last) if last.is_ascii_digit() => {
+        let rest = &argument[..argument.len() - 1];
+        let rest = rest.strip_suffix(['-', ' ']).unwrap_or(rest);
+        format!("{}-{}-Clause", rest, last)
+      }
+      _ => argument.to_owned(),
+    },
+    // e.g. 'BSD clause 3'
+    ["-clause-", "-clause ", " clause-", " clause "]
+      .iter()
+      .find_map(|separator| {
+        let index = argument.find(separator)?;
+        let digit = argument[index + separator.len()..].chars().next()?;
+        digit.is_ascii_digit().then(|| {
+          format!(
+            "{}-{}-Clause{}",
+            &argument[..index],
+            digit,
+            &argument[index + separator.len() + 1..]
+          )
+        })
+      })
+      .unwrap_or_else(|| argument.to_owned()),
+    // e.g. 'New BSD license'
+    replace_bsd(argument, &["modified", "new", "revised"], |_| {
+      "BSD-3-Clause".to_owned()
+    }),
+    // e.g. 'Simplified BSD license'
+    replace_bsd(argument, &["simplified"], |_| "BSD-2-Clause".to_owned()),
+    // e.g. 'Free BSD license'
+    replace_bsd(argument, &["free", "net"], |prefix| {
+      format!("BSD-2-Clause-{}BSD", prefix)
+    }),
+    // e.g. 'Clear BSD license'
+    replace_bsd(argument, &["clear"], |_| "BSD-3-Clause-Clear".to_owned()),
+    // e.g. 'Old BSD License'
+    replace_bsd(argument, &["old", "original"], |_| {
+      "BSD-4-Clause".to_owned()
+    }),
+    // e.g. 'BY-NC-4.0'
+    format!("CC-{}", argument),
+    // e.g. 'BY-NC'
+    format!("CC-{}-4.0", argument),
+    // e.g. 'Attribution-NonCommercial'
+    attribution.clone(),
+    // e.g. 'Attribution-NonCommercial'
+    format!("CC-{}-4.0", attribution),
+  ]
+}
+
+/// Replace the first digit which is preceded by one of `keywords`, the whitespaces around the keyword and the optional `separators` before it with `replacement`, and append `suffix` to the digit.
+fn replace_digit(
+  argument: &str,
+  keywords: &[&str],
+  separators: &str,
+  replacement: &str,
+  suffix: &str,
+) -> String {
+  for (index, digit) in argument.char_indices() {
+    if !digit.is_ascii_digit() {
+      continue;
+    }
+    let before = argument[..index].trim_end();
+    let Some(before) = keywords
+      .iter()
+      .filter_map(|keyword| before.strip_suffix(keyword))
+      .min_by_key(|before| before.len())
+    else {
+      continue;
+    };
+    let before = before.trim_end_matches(|char: char| separators.contains(char));
+    return format!(
+      "{}{}{}{}{}",
+      before,
+      replacement,
+      digit,
+      suffix,
+      &argument[index + 1..]
+    );
+  }
+  argument.to_owned()
+}
+
+/// Replace the first `<prefix>BSD[ License]` case-insensitively, with an optional `-` or space between words.
+fn replace_bsd(argument: &str, prefixes: &[&str], replacement: impl Fn(&str) -> String) -> String {
+  let lowercase = argument.to_ascii_lowercase();
+  for (start, _) in lowercase.char_indices() {
+    if lowercase[..start]
+      .chars()
+      .last()
+      .is_some_and(|char| char.is_alphanumeric() || char == '_')
+    {
+      continue;
+    }
+    for prefix in prefixes {
+      let Some(rest) = lowercase[start..].strip_prefix(prefix) else {
+        continue;
+      };
+      let rest = rest.strip_prefix(['-', ' ']).unwrap_or(rest);
+      let Some(rest) = rest.strip_prefix("bsd") else {
+        continue;
+      };
+      let rest = [" license", "-license"]
+        .iter()
+        .find_map(|license| rest.strip_prefix(license))
+        .unwrap_or(rest);
+      let mut capitalized = prefix.chars();
+      let capitalized = capitalized
+        .next()
+        .map(|first| first.to_uppercase().chain(capitalized).collect::<String>())
+        .unwrap_or_default();
+      return format!(
+        "{}{}{}",
+        &argument[..start],
+        replacement(&capitalized),
+        &argument[argument.len() - rest.len()..]
+      );
+    }
+  }
+  argument.to_owned()
+}
+
+fn valid_transformation(identifier: &str) -> Option<String> {
+  transforms(identifier).into_iter().find_map(|transformed| {
+    let transformed = transformed.trim();
+    (transformed != identifier && is_known_expression(transformed)).then(|| transformed.to_owned())
+  })
+}
+
+fn valid_last_resort(identifier: &str) -> Option<String> {
+  let uppercase = identifier.to_uppercase();
+  last_resorts()
+    .into_iter()
+    .find(|(substring, _)| uppercase.contains(substring.as_str()))
+    .map(|(_, id)| id)
+}
+
+/// The [LAST_RESORTS] and the licenses which only have one version, sorted by the length of substrings.
+fn last_resorts() -> Vec<(String, String)> {
+  let mut versions = Vec::<(&str, Vec<&str>)>::new();
+  for id in LICENSE_IDS {
+    let Some(name) = strip_version(id) else {
+      continue;
+    };
+    match versions.iter_mut().find(|(key, _)| *key == name) {
+      Some((_, ids)) => ids.push(id),
+      None => versions.push((name, vec![id])),
+    }
+  }
+
+  let mut last_resorts = LAST_RESORTS
+    .iter()
+    .map(|(substring, id)| (substring.to_string(), id.to_string()))
+    .chain(
+      versions
+        .into_iter()
+        .filter(|(name, ids)| ids.len() == 1 && *name != "APL")
+        .map(|(name, ids)| (name.to_owned(), ids[0].to_owned())),
+    )
+    .collect::<Vec<_>>();
+  last_resorts.sort_by(|(a, _), (b, _)| {
+    b.len()
+      .cmp(&a.len())
+      .then_with(|| a.to_uppercase().cmp(&b.to_uppercase()))
+  });
+  last_resorts
+}
+
+/// Strip the `-<major>.<minor>` suffix of a license id, e.g. `Apache` of `Apache-2.0`.
+fn strip_version(id: &str) -> Option<&str> {
+  let (name, version) = id.rsplit_once('-')?;
+  let (major, minor) = version.split_once('.')?;
+  let is_number = |text: &str| !text.is_empty() && text.chars().all(|char| char.is_ascii_digit());
+  (is_number(major) && is_number(minor)).then_some(name)
+}
+
+fn any_correction(identifier: &str, check: impl Fn(&str) -> Option<String>) -> Option<String> {
+  let mut transpositions = TRANSPOSITIONS.to_vec();
+  transpositions.sort_by(|(a, _), (b, _)| {
+    b.len()
+      .cmp(&a.len())
+      .then_with(|| a.to_uppercase().cmp(&b.to_uppercase()))
+  });
+  transpositions
+    .into_iter()
+    .filter(|(transposed, _)| identifier.contains(transposed))
+    .find_map(|(transposed, correct)| check(&identifier.replacen(transposed, correct, 1)))
+}
+
+/// Upgrade the deprecated GPL family identifiers, e.g. `GPL-2.0` to `GPL-2.0-only`.
+fn upgrade_gpl(id: &str) -> String {
+  match id {
+    "GPL-1.0" | "LGPL-1.0" | "AGPL-1.0" | "GPL-2.0" | "LGPL-2.0" | "AGPL-2.0" | "LGPL-2.1" => {
+      format!("{}-only", id)
+    }
+    "GPL-1.0+" | "GPL-2.0+" | "GPL-3.0+" | "LGPL-2.0+" | "LGPL-2.1+" | "LGPL-3.0+"
+    | "AGPL-1.0+" | "AGPL-3.0+" => format!("{}-or-later", &id[..id.len() - 1]),
+    "GPL-3.0" | "LGPL-3.0" | "AGPL-3.0" => format!("{}-or-later", id),
+    _ => id.to_owned(),
+  }
+}
+
+#[test]
+fn test_correct_license() {
+  for (identifier, corrected) in [
+    ("MIT", Some("MIT")),
+    ("mit", Some("MIT")),
+    ("MIT ", Some("MIT")),
+    ("M.I.T.", Some("MIT")),
+    ("MIT License", Some("MIT")),
+    ("Apache 2", Some("Apache-2.0")),
+    ("Apache 2.0", Some("Apache-2.0")),
+    ("Apache- 2.0", Some("Apache-2.0")),
+    ("Apache Version 2.0", Some("Apache-2.0")),
+    ("Apache License, Version 2.0", Some("Apache-2.0")),
+    ("CC BY 4.0", Some("CC-BY-4.0")),
+    ("LGPLv2.1", Some("LGPL-2.1-only")),
+    ("GPL 2", Some("GPL-2.0-only")),
+    ("GPL-3.0", Some("GPL-3.0-or-later")),
+    ("GPL-2.0+", Some("GPL-2.0-or-later")),
+    ("ZLIB", Some("Zlib")),
+    ("MPL/2.0", Some("MPL-2.0")),
+    ("BSD 3", Some("BSD-3-Clause")),
+    ("BSD clause 3", Some("BSD-3-Clause")),
+    ("New BSD license", Some("BSD-3-Clause")),
+    ("Simplified BSD license", Some("BSD-2-Clause")),
+    ("Free BSD license", Some("BSD-2-Clause-FreeBSD")),
+    ("Old BSD License", Some("BSD-4-Clause")),
+    ("BY-NC-4.0", Some("CC-BY-NC-4.0")),
+    ("Attribution-NonCommercial", Some("CC-BY-NC-4.0")),
+    ("GNU GPL", Some("GPL-3.0-or-later")),
+    ("The Unlicense", Some("Unlicense")),
+    ("WTFGPL", Some("WTFPL")),
+    ("Apache", Some("Apache-2.0")),
+    ("", None),
+    ("something else", None),
+  ] {
+    assert_eq!(
+      correct_license(identifier).as_deref(),
+      corrected,
+      "{:?}",
+      identifier
+    );
+  }
+}
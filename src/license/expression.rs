@@ -0,0 +1,322 @@
+use crate::error::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed [SPDX license expression](https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/) like `MIT OR (Apache-2.0 AND BSD-3-Clause)`.
+///
+/// The parser only checks the syntax, identifiers which aren't in the SPDX license list are reported by [validate_license](crate::validate_license).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseExpression {
+  /// A license like `GPL-2.0+ WITH Classpath-exception-2.0`, `id` may also be a user defined reference like `LicenseRef-Custom` or `DocumentRef-spdx-tool-1.2:LicenseRef-MIT-Style-2`.
+  License {
+    id: String,
+    /// Whether the license is suffixed with `+`, i.e. the license or any later version.
+    or_later: bool,
+    exception: Option<String>,
+  },
+  And(Box<LicenseExpression>, Box<LicenseExpression>),
+  Or(Box<LicenseExpression>, Box<LicenseExpression>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'a> {
+  Open,
+  Close,
+  Word(&'a str),
+}
+
+impl LicenseExpression {
+  /// Parse a license expression, `AND` has a higher precedence than `OR`.
+  /// ```
+  /// use package_json::LicenseExpression;
+  ///
+  /// let expression = LicenseExpression::parse("MIT OR (Apache-2.0 AND BSD-3-Clause)").unwrap();
+  /// assert_eq!(expression.licenses(), vec!["MIT", "Apache-2.0", "BSD-3-Clause"]);
+  /// assert!(LicenseExpression::parse("MIT OR").is_err());
+  /// ```
+  pub fn parse(expression: &str) -> Result<Self> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser { tokens, index: 0 };
+    let parsed = parser.parse_or().ok_or_else(|| {
+      Error::Validation(format!("Invalid license expression \"{}\".", expression))
+    })?;
+    if parser.index != parser.tokens.len() {
+      return Err(Error::Validation(format!(
+        "Invalid license expression \"{}\".",
+        expression
+      )));
+    }
+    Ok(parsed)
+  }
+
+  /// All license identifiers in the expression, in order of appearance.
+  pub fn licenses(&self) -> Vec<&str> {
+    match self {
+      Self::License { id, .. } => vec![id.as_str()],
+      Self::And(left, right) | Self::Or(left, right) => {
+        let mut licenses = left.licenses();
+        licenses.extend(right.licenses());
+        licenses
+      }
+    }
+  }
+
+  /// All license exception identifiers in the expression, in order of appearance.
+  pub fn exceptions(&self) -> Vec<&str> {
+    match self {
+      Self::License { exception, .. } => exception.iter().map(String::as_str).collect(),
+      Self::And(left, right) | Self::Or(left, right) => {
+        let mut exceptions = left.exceptions();
+        exceptions.extend(right.exceptions());
+        exceptions
+      }
+    }
+  }
+}
+
+/// Whether `id` is a user defined license reference like `LicenseRef-Custom` or `DocumentRef-spdx-tool-1.2:LicenseRef-MIT-Style-2`.
+pub(crate) fn is_license_ref(id: &str) -> bool {
+  let id = match id.split_once(':') {
+    Some((document, id)) if document.starts_with("DocumentRef-") => id,
+    _ => id,
+  };
+  id.strip_prefix("LicenseRef-")
+    .is_some_and(|id| !id.is_empty() && is_idstring(id))
+}
+
+fn is_idstring(id: &str) -> bool {
+  !id.is_empty()
+    && id
+      .chars()
+      .all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '.')
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token<'_>>> {
+  let mut tokens = Vec::new();
+  let mut rest = expression.trim_start();
+  while let Some(char) = rest.chars().next() {
+    match char {
+      '(' => {
+        tokens.push(Token::Open);
+        rest = &rest[1..];
+      }
+      ')' => {
+        tokens.push(Token::Close);
+        rest = &rest[1..];
+      }
+      _ => {
+        let end = rest
+          .find(|char: char| char.is_whitespace() || char == '(' || char == ')')
+          .unwrap_or(rest.len());
+        tokens.push(Token::Word(&rest[..end]));
+        rest = &rest[end..];
+      }
+    }
+    rest = rest.trim_start();
+  }
+  if tokens.is_empty() {
+    return Err(Error::Validation(format!(
+      "Invalid license expression \"{}\": empty expression.",
+      expression
+    )));
+  }
+  Ok(tokens)
+}
+
+struct Parser<'a> {
+  tokens: Vec<Token<'a>>,
+  index: usize,
+}
+
+impl Parser<'_> {
+  fn eat(&mut self, token: Token) -> bool {
+    let matched = self.tokens.get(self.index) == Some(&token);
+    if matched {
+      self.index += 1;
+    }
+    matched
+  }
+
+  fn parse_or(&mut self) -> Option<LicenseExpression> {
+    let mut expression = self.parse_and()?;
+    while self.eat(Token::Word("OR")) {
+      expression = LicenseExpression::Or(Box::new(expression), Box::new(self.parse_and()?));
+    }
+    Some(expression)
+  }
+
+  fn parse_and(&mut self) -> Option<LicenseExpression> {
+    let mut expression = self.parse_atom()?;
+    while self.eat(Token::Word("AND")) {
+      expression = LicenseExpression::And(Box::new(expression), Box::new(self.parse_atom()?));
+    }
+    Some(expression)
+  }
+
+  fn parse_atom(&mut self) -> Option<LicenseExpression> {
+    if self.eat(Token::Open) {
+      let expression = self.parse_or()?;
+      return self.eat(Token::Close).then_some(expression);
+    }
+
+    let word = match self.tokens.get(self.index)? {
+      Token::Word(word) if !matches!(*word, "AND" | "OR" | "WITH") => *word,
+      _ => return None,
+    };
+    self.index += 1;
+    let (id, or_later) = match word.strip_suffix('+') {
+      Some(id) => (id, true),
+      None => (word, false),
+    };
+    let valid = if id.starts_with("LicenseRef-") || id.starts_with("DocumentRef-") {
+      is_license_ref(id) && !or_later
+    } else {
+      is_idstring(id)
+    };
+    if !valid {
+      return None;
+    }
+
+    let exception = if self.eat(Token::Word("WITH")) {
+      match self.tokens.get(self.index)? {
+        Token::Word(exception) if is_idstring(exception) => {
+          self.index += 1;
+          Some(exception.to_string())
+        }
+        _ => return None,
+      }
+    } else {
+      None
+    };
+
+    Some(LicenseExpression::License {
+      id: id.to_owned(),
+      or_later,
+      exception,
+    })
+  }
+}
+
+impl fmt::Display for LicenseExpression {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::License {
+        id,
+        or_later,
+        exception,
+      } => {
+        write!(f, "{}", id)?;
+        if *or_later {
+          write!(f, "+")?;
+        }
+        if let Some(exception) = exception {
+          write!(f, " WITH {}", exception)?;
+        }
+        Ok(())
+      }
+      Self::And(left, right) => {
+        for (index, operand) in [left, right].into_iter().enumerate() {
+          if index > 0 {
+            write!(f, " AND ")?;
+          }
+          match operand.as_ref() {
+            Self::Or(..) => write!(f, "({})", operand)?,
+            _ => write!(f, "{}", operand)?,
+          }
+        }
+        Ok(())
+      }
+      Self::Or(left, right) => write!(f, "{} OR {}", left, right),
+    }
+  }
+}
+
+impl FromStr for LicenseExpression {
+  type Err = Error;
+
+  fn from_str(expression: &str) -> Result<Self> {
+    Self::parse(expression)
+  }
+}
+
+#[test]
+fn test_parse() {
+  let license = |id: &str| LicenseExpression::License {
+    id: id.to_owned(),
+    or_later: false,
+    exception: None,
+  };
+
+  assert_eq!(LicenseExpression::parse("MIT").unwrap(), license("MIT"));
+  assert_eq!(
+    LicenseExpression::parse("MIT OR (Apache-2.0 AND BSD-3-Clause)").unwrap(),
+    LicenseExpression::Or(
+      Box::new(license("MIT")),
+      Box::new(LicenseExpression::And(
+        Box::new(license("Apache-2.0")),
+        Box::new(license("BSD-3-Clause"))
+      ))
+    )
+  );
+  assert_eq!(
+    LicenseExpression::parse("MIT OR ISC AND 0BSD").unwrap(),
+    LicenseExpression::Or(
+      Box::new(license("MIT")),
+      Box::new(LicenseExpression::And(
+        Box::new(license("ISC")),
+        Box::new(license("0BSD"))
+      ))
+    )
+  );
+  assert_eq!(
+    LicenseExpression::parse("GPL-2.0+ WITH Classpath-exception-2.0").unwrap(),
+    LicenseExpression::License {
+      id: "GPL-2.0".to_owned(),
+      or_later: true,
+      exception: Some("Classpath-exception-2.0".to_owned()),
+    }
+  );
+  assert_eq!(
+    LicenseExpression::parse("DocumentRef-spdx-tool-1.2:LicenseRef-MIT-Style-2").unwrap(),
+    license("DocumentRef-spdx-tool-1.2:LicenseRef-MIT-Style-2")
+  );
+
+  for invalid in [
+    "",
+    "MIT OR",
+    "AND MIT",
+    "(MIT",
+    "MIT)",
+    "MIT WITH",
+    "MIT ISC",
+    "Apache 2.0",
+    "LicenseRef-",
+    "MIT/X11",
+  ] {
+    assert!(LicenseExpression::parse(invalid).is_err(), "{:?}", invalid);
+  }
+}
+
+#[test]
+fn test_display() {
+  for (expression, displayed) in [
+    ("MIT", "MIT"),
+    ("(MIT)", "MIT"),
+    (
+      "MIT OR (Apache-2.0 AND BSD-3-Clause)",
+      "MIT OR Apache-2.0 AND BSD-3-Clause",
+    ),
+    ("(MIT OR ISC) AND 0BSD", "(MIT OR ISC) AND 0BSD"),
+    (
+      "GPL-2.0+  WITH  Classpath-exception-2.0",
+      "GPL-2.0+ WITH Classpath-exception-2.0",
+    ),
+  ] {
+    let parsed = LicenseExpression::parse(expression).unwrap();
+    assert_eq!(parsed.to_string(), displayed);
+    assert_eq!(
+      LicenseExpression::parse(displayed).unwrap().to_string(),
+      displayed
+    );
+  }
+}
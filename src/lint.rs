@@ -0,0 +1,325 @@
+//! A configurable linter of `package.json`, which runs [Rule]s over a [PackageJson] and reports [Diagnostic]s.
+
+use crate::PackageJson;
+use indexmap::IndexMap;
+use std::fmt;
+use std::path::Path;
+
+pub(crate) mod rules;
+
+/// How serious a [Diagnostic] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+/// A problem reported by a [Rule].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+  /// The [name][Rule::name] of the rule which reports the problem.
+  pub rule: String,
+  pub severity: Severity,
+  /// The [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) of the offending field, e.g. `/devDependencies/typescript`.
+  pub pointer: String,
+  pub message: String,
+}
+
+/// A problem found by [Rule::check], which is turned into a [Diagnostic] with the configured severity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+  /// The [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) of the offending field, see [json_pointer].
+  pub pointer: String,
+  pub message: String,
+}
+
+/// The environment of the linted package.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LintContext<'a> {
+  /// The directory which contains the `package.json` file, rules which check the file system are skipped without it.
+  pub package_dir: Option<&'a Path>,
+  /// The top-level keys of the source `package.json`, which tell the fields filled by their defaults apart from the written ones, e.g. [main][PackageJson::main]. Every field is taken as written without it.
+  pub keys: Option<&'a [String]>,
+}
+
+impl LintContext<'_> {
+  /// Whether `field` is written in the source `package.json`, it's always true without [LintContext::keys].
+  pub fn is_written(&self, field: &str) -> bool {
+    self
+      .keys
+      .is_none_or(|keys| keys.iter().any(|key| key == field))
+  }
+}
+
+/// A lint rule. Implement it to register a custom rule with [Linter::register].
+/// ```
+/// use package_json::{json_pointer, LintContext, Linter, PackageJson, Rule, Violation};
+///
+/// struct DescriptionRequired;
+///
+/// impl Rule for DescriptionRequired {
+///   fn name(&self) -> &str {
+///     "description-required"
+///   }
+///
+///   fn check(&self, json: &PackageJson, _: &LintContext) -> Vec<Violation> {
+///     match json.description {
+///       Some(_) => vec![],
+///       None => vec![Violation {
+///         pointer: json_pointer(&["description"]),
+///         message: "The description is missing.".to_owned(),
+///       }],
+///     }
+///   }
+/// }
+///
+/// let mut linter = Linter::new();
+/// linter.register(DescriptionRequired);
+/// let diagnostics = linter.lint(&PackageJson::default(), &LintContext::default());
+/// assert!(diagnostics.iter().any(|diagnostic| diagnostic.rule == "description-required"));
+/// ```
+pub trait Rule {
+  /// The unique name of the rule, which is used to configure it.
+  fn name(&self) -> &str;
+
+  /// The severity of the rule when it isn't configured.
+  fn default_severity(&self) -> Severity {
+    Severity::Warning
+  }
+
+  fn check(&self, json: &PackageJson, context: &LintContext) -> Vec<Violation>;
+}
+
+/// Runs a set of [Rule]s. Every rule is enabled with its [default severity][Rule::default_severity] unless it's configured by [Linter::set_severity] or [Linter::disable].
+pub struct Linter {
+  rules: Vec<Box<dyn Rule>>,
+  /// The configured severities, `None` means the rule is disabled.
+  severities: IndexMap<String, Option<Severity>>,
+}
+
+impl Default for Linter {
+  fn default() -> Self {
+    let mut linter = Self::empty();
+    linter
+      .register(rules::LicenseRequired)
+      .register(rules::RepositoryRequired)
+      .register(rules::MainExists)
+      .register(rules::DuplicateDependencies)
+      .register(rules::NoWildcardRange::default());
+    linter
+  }
+}
+
+impl Linter {
+  /// Construct a linter with all built-in rules, they are:
+  ///
+  /// - `license-required`: a public package should have a license.
+  /// - `repository-required`: a public package should have a repository.
+  /// - `main-exists`: the `main` file should exist.
+  /// - `duplicate-dependencies`: a package shouldn't be in both `dependencies` and `devDependencies`.
+  /// - `no-wildcard-range`: a dependency shouldn't accept any version, e.g. `*`, see [NoWildcardRange](crate::NoWildcardRange).
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Construct a linter without any rule.
+  pub fn empty() -> Self {
+    Self {
+      rules: Vec::new(),
+      severities: IndexMap::new(),
+    }
+  }
+
+  /// Register a rule, it replaces the registered rule with the same name, which is the way to reconfigure a built-in rule.
+  pub fn register<R: Rule + 'static>(&mut self, rule: R) -> &mut Self {
+    match self
+      .rules
+      .iter_mut()
+      .find(|registered| registered.name() == rule.name())
+    {
+      Some(registered) => *registered = Box::new(rule),
+      None => self.rules.push(Box::new(rule)),
+    }
+    self
+  }
+
+  /// Enable a rule with its default severity.
+  pub fn enable(&mut self, name: &str) -> &mut Self {
+    self.severities.shift_remove(name);
+    self
+  }
+
+  /// Disable a rule, so that it never reports.
+  pub fn disable(&mut self, name: &str) -> &mut Self {
+    self.severities.insert(name.to_owned(), None);
+    self
+  }
+
+  /// Enable a rule with the given severity.
+  pub fn set_severity(&mut self, name: &str, severity: Severity) -> &mut Self {
+    self.severities.insert(name.to_owned(), Some(severity));
+    self
+  }
+
+  /// The names of the registered rules, in order of registration.
+  pub fn rule_names(&self) -> Vec<&str> {
+    self.rules.iter().map(|rule| rule.name()).collect()
+  }
+
+  /// Run all enabled rules, the diagnostics are sorted by rule registration order.
+  pub fn lint(&self, json: &PackageJson, context: &LintContext) -> Vec<Diagnostic> {
+    self
+      .rules
+      .iter()
+      .filter_map(|rule| {
+        let severity = match self.severities.get(rule.name()) {
+          Some(severity) => (*severity)?,
+          None => rule.default_severity(),
+        };
+        Some((rule, severity))
+      })
+      .flat_map(|(rule, severity)| {
+        rule
+          .check(json, context)
+          .into_iter()
+          .map(move |violation| Diagnostic {
+            rule: rule.name().to_owned(),
+            severity,
+            pointer: violation.pointer,
+            message: violation.message,
+          })
+      })
+      .collect()
+  }
+}
+
+impl fmt::Debug for Linter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Linter")
+      .field("rules", &self.rule_names())
+      .field("severities", &self.severities)
+      .finish()
+  }
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Warning => "warning",
+      Self::Error => "error",
+    })
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} {}: {} ({})",
+      self.severity, self.pointer, self.message, self.rule
+    )
+  }
+}
+
+/// Build a [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) from the keys, e.g. `/dependencies/@scope~1pkg` of `["dependencies", "@scope/pkg"]`.
+pub fn json_pointer(keys: &[&str]) -> String {
+  keys
+    .iter()
+    .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+    .collect()
+}
+
+impl PackageJson {
+  /// Lint the package with the [built-in rules](Linter::new).
+  pub fn lint(&self, context: &LintContext) -> Vec<Diagnostic> {
+    Linter::new().lint(self, context)
+  }
+}
+
+#[test]
+fn test_linter() {
+  struct NameRequired;
+
+  impl Rule for NameRequired {
+    fn name(&self) -> &str {
+      "name-required"
+    }
+
+    fn default_severity(&self) -> Severity {
+      Severity::Error
+    }
+
+    fn check(&self, json: &PackageJson, _: &LintContext) -> Vec<Violation> {
+      match json.name.is_empty() {
+        true => vec![Violation {
+          pointer: json_pointer(&["name"]),
+          message: "The name is missing.".to_owned(),
+        }],
+        false => vec![],
+      }
+    }
+  }
+
+  let json = PackageJson::default();
+  let context = LintContext::default();
+  let mut linter = Linter::new();
+  linter.register(NameRequired);
+  assert_eq!(
+    linter.rule_names(),
+    vec![
+      "license-required",
+      "repository-required",
+      "main-exists",
+      "duplicate-dependencies",
+      "no-wildcard-range",
+      "name-required"
+    ]
+  );
+
+  let rules = |linter: &Linter| {
+    linter
+      .lint(&json, &context)
+      .into_iter()
+      .map(|diagnostic| (diagnostic.rule, diagnostic.severity))
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(
+    rules(&linter),
+    vec![
+      ("license-required".to_owned(), Severity::Warning),
+      ("repository-required".to_owned(), Severity::Warning),
+      ("name-required".to_owned(), Severity::Error),
+    ]
+  );
+
+  linter
+    .disable("license-required")
+    .set_severity("repository-required", Severity::Error);
+  assert_eq!(
+    rules(&linter),
+    vec![
+      ("repository-required".to_owned(), Severity::Error),
+      ("name-required".to_owned(), Severity::Error),
+    ]
+  );
+
+  linter
+    .enable("license-required")
+    .enable("repository-required");
+  assert_eq!(rules(&linter).len(), 3);
+  assert_eq!(
+    linter.lint(&json, &context)[0].to_string(),
+    "warning /license: A public package should have a license. (license-required)"
+  );
+}
+
+#[test]
+fn test_json_pointer() {
+  assert_eq!(json_pointer(&[]), "");
+  assert_eq!(json_pointer(&["main"]), "/main");
+  assert_eq!(
+    json_pointer(&["dependencies", "@scope/pkg"]),
+    "/dependencies/@scope~1pkg"
+  );
+  assert_eq!(json_pointer(&["a~b"]), "/a~0b");
+}
@@ -0,0 +1,264 @@
+//! The built-in lint rules, see [Linter::new](crate::Linter::new).
+
+use crate::lint::{json_pointer, LintContext, Rule, Severity, Violation};
+use crate::{DependencySpec, PackageJson};
+
+/// A public package should have a `license` (or a legacy `licenses`) field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LicenseRequired;
+
+impl Rule for LicenseRequired {
+  fn name(&self) -> &str {
+    "license-required"
+  }
+
+  fn check(&self, json: &PackageJson, _: &LintContext) -> Vec<Violation> {
+    match json.private || json.license.is_some() || json.licenses.is_some() {
+      true => vec![],
+      false => vec![Violation {
+        pointer: json_pointer(&["license"]),
+        message: "A public package should have a license.".to_owned(),
+      }],
+    }
+  }
+}
+
+/// A public package should have a `repository` field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepositoryRequired;
+
+impl Rule for RepositoryRequired {
+  fn name(&self) -> &str {
+    "repository-required"
+  }
+
+  fn check(&self, json: &PackageJson, _: &LintContext) -> Vec<Violation> {
+    match json.private || json.repository.is_some() {
+      true => vec![],
+      false => vec![Violation {
+        pointer: json_pointer(&["repository"]),
+        message: "A public package should have a repository.".to_owned(),
+      }],
+    }
+  }
+}
+
+/// The `main` file should exist, it's resolved as `require` does, e.g. `lib` could be `lib.js` or `lib/index.js`. Skipped without [LintContext::package_dir], or when `main` isn't written in the source, e.g. a package with `exports` only.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MainExists;
+
+impl Rule for MainExists {
+  fn name(&self) -> &str {
+    "main-exists"
+  }
+
+  fn default_severity(&self) -> Severity {
+    Severity::Error
+  }
+
+  fn check(&self, json: &PackageJson, context: &LintContext) -> Vec<Violation> {
+    let Some(package_dir) = context.package_dir.filter(|_| context.is_written("main")) else {
+      return vec![];
+    };
+    let main = package_dir.join(&json.main);
+    let exists = main.is_file()
+      || [".js", ".json", ".node"].iter().any(|extension| {
+        let mut file = main.clone().into_os_string();
+        file.push(extension);
+        std::path::Path::new(&file).is_file()
+      })
+      || ["index.js", "index.json", "index.node"]
+        .iter()
+        .any(|index| main.join(index).is_file());
+    match exists {
+      true => vec![],
+      false => vec![Violation {
+        pointer: json_pointer(&["main"]),
+        message: format!("The main file {:?} doesn't exist.", json.main),
+      }],
+    }
+  }
+}
+
+/// A package shouldn't be in both `dependencies` and `devDependencies`, the entry of `devDependencies` is reported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateDependencies;
+
+impl Rule for DuplicateDependencies {
+  fn name(&self) -> &str {
+    "duplicate-dependencies"
+  }
+
+  fn check(&self, json: &PackageJson, _: &LintContext) -> Vec<Violation> {
+    let (Some(dependencies), Some(dev_dependencies)) = (&json.dependencies, &json.dev_dependencies)
+    else {
+      return vec![];
+    };
+    dev_dependencies
+      .keys()
+      .filter(|name| dependencies.contains_key(*name))
+      .map(|name| Violation {
+        pointer: json_pointer(&["devDependencies", name]),
+        message: format!("{:?} is in both dependencies and devDependencies.", name),
+      })
+      .collect()
+  }
+}
+
+/// A dependency shouldn't accept any version, e.g. `*`, `x` or an empty range.
+#[derive(Debug, Clone)]
+pub struct NoWildcardRange {
+  /// The checked dependency fields, they are `dependencies`, `devDependencies` and `optionalDependencies` by default. `peerDependencies` isn't checked by default since `*` is a common way to accept any version of the host package.
+  pub fields: Vec<String>,
+}
+
+impl Default for NoWildcardRange {
+  fn default() -> Self {
+    Self {
+      fields: ["dependencies", "devDependencies", "optionalDependencies"]
+        .map(str::to_owned)
+        .to_vec(),
+    }
+  }
+}
+
+impl Rule for NoWildcardRange {
+  fn name(&self) -> &str {
+    "no-wildcard-range"
+  }
+
+  fn check(&self, json: &PackageJson, _: &LintContext) -> Vec<Violation> {
+    self
+      .fields
+      .iter()
+      .filter_map(|field| {
+        let dependencies = match field.as_str() {
+          "dependencies" => json.dependencies.as_ref(),
+          "devDependencies" => json.dev_dependencies.as_ref(),
+          "peerDependencies" => json.peer_dependencies.as_ref(),
+          "optionalDependencies" => json.optional_dependencies.as_ref(),
+          _ => None,
+        }?;
+        Some((field, dependencies))
+      })
+      .flat_map(|(field, dependencies)| {
+        dependencies
+          .iter()
+          .filter(|(_, spec)| is_wildcard(spec))
+          .map(move |(name, spec)| Violation {
+            pointer: json_pointer(&[field, name]),
+            message: format!("{:?} accepts any version with {:?}.", name, spec),
+          })
+      })
+      .collect()
+  }
+}
+
+fn is_wildcard(spec: &str) -> bool {
+  DependencySpec::parse(spec)
+    .ok()
+    .and_then(|spec| spec.range())
+    .is_some_and(|range| range.comparator_sets().iter().any(Vec::is_empty))
+}
+
+#[test]
+fn test_rules() {
+  use crate::Linter;
+  use std::env::current_dir;
+  use tempfile::tempdir_in;
+
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "test",
+      "version": "1.0.0",
+      "main": "lib/main",
+      "dependencies": {
+        "a": "*",
+        "@scope/b": "^1.0.0",
+        "c": "x || ^2.0.0",
+        "d": "npm:e@",
+        "f": "workspace:*",
+        "g": "latest"
+      },
+      "devDependencies": {
+        "@scope/b": "^1.1.0",
+        "h": ""
+      },
+      "peerDependencies": {
+        "i": "*"
+      }
+    }"#,
+  )
+  .unwrap();
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let context = LintContext {
+    package_dir: Some(dir.path()),
+    ..Default::default()
+  };
+  let diagnostics = |linter: &Linter, json: &PackageJson| {
+    linter
+      .lint(json, &context)
+      .into_iter()
+      .map(|diagnostic| (diagnostic.rule, diagnostic.pointer))
+      .collect::<Vec<_>>()
+  };
+  let pairs = |pairs: &[(&str, &str)]| {
+    pairs
+      .iter()
+      .map(|(rule, pointer)| (rule.to_string(), pointer.to_string()))
+      .collect::<Vec<_>>()
+  };
+
+  let mut linter = Linter::new();
+  assert_eq!(
+    diagnostics(&linter, &json),
+    pairs(&[
+      ("license-required", "/license"),
+      ("repository-required", "/repository"),
+      ("main-exists", "/main"),
+      ("duplicate-dependencies", "/devDependencies/@scope~1b"),
+      ("no-wildcard-range", "/dependencies/a"),
+      ("no-wildcard-range", "/dependencies/c"),
+      ("no-wildcard-range", "/dependencies/d"),
+      ("no-wildcard-range", "/devDependencies/h"),
+    ])
+  );
+
+  std::fs::create_dir_all(dir.path().join("lib/main")).unwrap();
+  std::fs::write(dir.path().join("lib/main/index.js"), "").unwrap();
+  json.private = true;
+  linter.register(NoWildcardRange {
+    fields: vec!["peerDependencies".to_owned()],
+  });
+  assert_eq!(
+    diagnostics(&linter, &json),
+    pairs(&[
+      ("duplicate-dependencies", "/devDependencies/@scope~1b"),
+      ("no-wildcard-range", "/peerDependencies/i"),
+    ])
+  );
+
+  std::fs::remove_dir_all(dir.path().join("lib")).unwrap();
+  std::fs::create_dir_all(dir.path().join("lib")).unwrap();
+  std::fs::write(dir.path().join("lib/main.js"), "").unwrap();
+  assert!(MainExists.check(&json, &context).is_empty());
+  assert!(MainExists.check(&json, &LintContext::default()).is_empty());
+
+  // `main` is filled by its default when it's missing in the source.
+  let json = serde_json::from_str::<PackageJson>(
+    r#"{ "name": "test", "version": "1.0.0", "exports": "./lib/main.js" }"#,
+  )
+  .unwrap();
+  let keys = ["name", "version", "exports"].map(str::to_owned);
+  let context = LintContext {
+    package_dir: Some(dir.path()),
+    keys: Some(&keys),
+  };
+  assert!(MainExists.check(&json, &context).is_empty());
+  let context = LintContext {
+    keys: None,
+    ..context
+  };
+  assert_eq!(MainExists.check(&json, &context).len(), 1);
+}
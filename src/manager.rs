@@ -1,7 +1,9 @@
+use crate::error::{Error, Result};
 use crate::fs;
+use crate::fs::document;
 use crate::fs::write_options::WriteOptions;
-use crate::PackageJson;
-use anyhow::{format_err, Result};
+use crate::workspace::{self, MemberGlobs};
+use crate::{Diagnostic, LintContext, Linter, PackageJson, ReleaseType, ResolvedImport, Version};
 use std::env;
 use std::path::{Path, PathBuf};
 
@@ -13,6 +15,12 @@ pub struct PackageJsonManager {
   file_path: Option<PathBuf>,
   json: PackageJson,
   write_options: Option<WriteOptions>,
+  /// The contents of the last read or written `package.json` file.
+  contents: Option<String>,
+  /// The file which `contents` is read from or written to, which differs from `file_path` after another file is located.
+  contents_path: Option<PathBuf>,
+  /// The serialized `PackageJson` which `contents` represents.
+  snapshot: Option<serde_json::Value>,
 }
 
 impl Default for PackageJsonManager {
@@ -21,6 +29,9 @@ impl Default for PackageJsonManager {
       file_path: None,
       json: Default::default(),
       write_options: Some(WriteOptions::default()),
+      contents: None,
+      contents_path: None,
+      snapshot: None,
     }
   }
 }
@@ -64,7 +75,11 @@ impl PackageJsonManager {
 
   /// Try to locate the closest `package.json` file from [current working directory][std::env::current_dir] to sys root.
   pub fn locate_closest(&mut self) -> Result<PathBuf> {
-    env::current_dir().map(|cwd| self.locate_closest_from(cwd))?
+    let cwd = env::current_dir().map_err(|source| Error::Io {
+      path: PathBuf::from("."),
+      source,
+    })?;
+    self.locate_closest_from(cwd)
   }
 
   /// Try to locate the closest `package.json` file from specific directory to sys root.
@@ -75,13 +90,49 @@ impl PackageJsonManager {
     })
   }
 
+  /// Try to locate the workspace root from [current working directory][std::env::current_dir], see [locate_workspace_root_from][PackageJsonManager::locate_workspace_root_from].
+  pub fn locate_workspace_root(&mut self) -> Result<(PathBuf, PathBuf)> {
+    let cwd = env::current_dir().map_err(|source| Error::Io {
+      path: PathBuf::from("."),
+      source,
+    })?;
+    self.locate_workspace_root_from(cwd)
+  }
+
+  /// Try to locate the root `package.json` of the workspace which includes the closest `package.json` from specific directory. It walks up past the ancestors until a `package.json` whose `workspaces` (or a sibling `pnpm-workspace.yaml`) includes the closest one, which becomes the located file.
+  ///
+  /// Return the paths of the root and the closest `package.json`. If no workspace includes the closest `package.json`, it's the root of itself, so that both paths are the same.
+  /// ```no_run
+  /// use package_json::PackageJsonManager;
+  ///
+  /// let mut manager = PackageJsonManager::new();
+  /// if let Ok((root, member)) = manager.locate_workspace_root_from("/path/to/monorepo/packages/a/src") {
+  ///   println!("{} is a member of {}", member.display(), root.display());
+  /// }
+  /// ```
+  pub fn locate_workspace_root_from<P: AsRef<Path>>(
+    &mut self,
+    from: P,
+  ) -> Result<(PathBuf, PathBuf)> {
+    let member = fs::find_closest_file(PACKAGE_JSON_FILENAME, from)?;
+    let member_dir = member.parent().unwrap_or(Path::new(""));
+    let root = member_dir
+      .ancestors()
+      .skip(1)
+      .map(|dir| dir.join(PACKAGE_JSON_FILENAME))
+      .find(|file_path| includes_member(file_path, member_dir))
+      .unwrap_or_else(|| member.clone());
+    self.file_path = Some(root.clone());
+    Ok((root, member))
+  }
+
   /// Specify the `package.json` file path which is used to read and write.
   pub fn set_file_path<FilePath: AsRef<Path>>(&mut self, file_path: FilePath) {
     self.file_path = Some(file_path.as_ref().to_path_buf());
   }
 
   /// Get the located file path after `locate_closest` or `locate_closest_from` evaluated.
-  pub fn get_file_path(&mut self) -> Option<&Path> {
+  pub fn get_file_path(&self) -> Option<&Path> {
     self.file_path.as_deref()
   }
 
@@ -92,20 +143,37 @@ impl PackageJsonManager {
 
   /// Call file reader to read `package.json` file.
   fn read(&mut self) -> Result<()> {
+    self
+      .read_with(|contents| Ok((fs::parse_json(contents)?, Vec::new())))
+      .map(|_| ())
+  }
+
+  /// Read `package.json` file with the given parser, return the warnings of the parser.
+  fn read_with<F>(&mut self, parse: F) -> Result<Vec<String>>
+  where
+    F: FnOnce(&str) -> Result<(PackageJson, Vec<String>)>,
+  {
     self
       .file_path
       .as_ref()
       .map(|file_path| {
-        fs::read_json(file_path).map(|json| {
-          self.json = json;
-        })
-      })
-      .unwrap_or_else(|| {
-        Err(format_err!(
-          "Couldn't find an available {} file.",
-          PACKAGE_JSON_FILENAME
-        ))
+        let contents = fs::read_file(file_path)?;
+        let (json, warnings) = parse(&contents).map_err(|error| match error {
+          Error::Parse(error) => Error::Parse(error.with_file_path(file_path)),
+          error => error,
+        })?;
+        self.json = json;
+        if let Some(write_options) = self.write_options.as_mut() {
+          if write_options.detect_format {
+            write_options.detect_from(&contents);
+          }
+        }
+        self.snapshot = Some(serde_json::to_value(&self.json).map_err(Error::Serialize)?);
+        self.contents = Some(contents);
+        self.contents_path = Some(file_path.clone());
+        Ok(warnings)
       })
+      .unwrap_or_else(|| Err(not_located()))
   }
 
   ///
@@ -137,6 +205,20 @@ impl PackageJsonManager {
     self.read().map(|_| &mut self.json)
   }
 
+  /// Read `package.json` file in the lenient mode, see [PackageJson::parse_lenient]. Return the warnings of the recovered fields, the `PackageJson` is available by [as_ref][PackageJsonManager::as_ref] or [as_mut][PackageJsonManager::as_mut].
+  /// ```
+  /// use package_json::PackageJsonManager;
+  /// let mut manager = PackageJsonManager::new();
+  /// if manager.locate_closest().is_ok() {
+  ///   if let Ok(warnings) = manager.read_lenient() {
+  ///     warnings.iter().for_each(|warning| println!("{}", warning));
+  ///   }
+  /// }
+  /// ```
+  pub fn read_lenient(&mut self) -> Result<Vec<String>> {
+    self.read_with(PackageJson::parse_lenient)
+  }
+
   /// Use the current `package.json` content to write the target `package.json` file.
   /// ```
   /// use package_json::PackageJsonManager;
@@ -150,25 +232,8 @@ impl PackageJsonManager {
   /// }
   /// ```
   pub fn write(&mut self) -> Result<()> {
-    self
-      .file_path
-      .as_ref()
-      .map(|file_path| {
-        fs::write_json(
-          file_path,
-          &self.json,
-          self
-            .write_options
-            .as_ref()
-            .expect("self.write_options should not be None"),
-        )
-      })
-      .unwrap_or_else(|| {
-        Err(format_err!(
-          "Couldn't find an available {} file.",
-          PACKAGE_JSON_FILENAME
-        ))
-      })
+    let file_path = self.file_path.clone().ok_or_else(not_located)?;
+    self.write_to(&file_path)
   }
 
   /// Write the current `package.json` content to the specific `package.json` file.
@@ -187,14 +252,127 @@ impl PackageJsonManager {
   /// }
   /// ```
   pub fn write_to(&mut self, file_path: &Path) -> Result<()> {
-    fs::write_json(
-      file_path,
-      &self.json,
-      self
-        .write_options
+    let write_options = self
+      .write_options
+      .as_ref()
+      .expect("self.write_options should not be None");
+    if let Some(format) = write_options.people_format {
+      self.json.normalize_people(format);
+    }
+    match (&self.contents, &self.snapshot) {
+      (Some(contents), Some(snapshot)) if write_options.preserve_format => {
+        let value = serde_json::to_value(&self.json).map_err(Error::Serialize)?;
+        let contents = document::patch(contents, snapshot, &value)?;
+        fs::write_file(file_path, &contents)?;
+        self.contents = Some(contents);
+        self.contents_path = Some(file_path.to_path_buf());
+        self.snapshot = Some(value);
+        Ok(())
+      }
+      _ => fs::write_json(file_path, self.to_ordered_value()?, write_options),
+    }
+  }
+
+  /// Increase the version of the current `PackageJson` by `release` as `npm version` does, and write it back with the other unsaved edits. The file is read first if it hasn't been read, or another file has been located since it's read. Return the new version.
+  /// ```no_run
+  /// use package_json::{PackageJsonManager, ReleaseType};
+  /// let mut manager = PackageJsonManager::new();
+  /// if manager.locate_closest().is_ok() {
+  ///   if let Ok(version) = manager.bump(ReleaseType::Patch) {
+  ///     println!("bumped to v{}", version);
+  ///   }
+  /// }
+  /// ```
+  pub fn bump(&mut self, release: ReleaseType) -> Result<Version> {
+    if self.contents.is_none() || self.contents_path != self.file_path {
+      self.read()?;
+    }
+    let version = self.json.bump(release)?;
+    self.write()?;
+    Ok(version)
+  }
+
+  /// Resolve a private `#` specifier by the `imports` field of the current `package.json`, relative to the directory of the located file. See [PackageJson::resolve_import].
+  /// ```
+  /// use package_json::{PackageJsonManager, ResolvedImport};
+  /// let mut manager = PackageJsonManager::new();
+  /// if manager.locate_closest().is_ok() && manager.read_ref().is_ok() {
+  ///   if let Ok(ResolvedImport::Path(path)) = manager.resolve_import("#utils", &["node", "import"]) {
+  ///     println!("#utils is resolved to {}", path.display());
+  ///   }
+  /// }
+  /// ```
+  pub fn resolve_import(&self, specifier: &str, conditions: &[&str]) -> Result<ResolvedImport> {
+    let package_dir = self
+      .file_path
+      .as_ref()
+      .and_then(|file_path| file_path.parent())
+      .ok_or_else(not_located)?;
+    let target = self.json.resolve_import(specifier, conditions)?;
+    Ok(ResolvedImport::new(target, package_dir))
+  }
+
+  /// Lint the current `package.json` by `linter`, the file system rules are checked against the directory of the located file.
+  /// ```
+  /// use package_json::{Linter, PackageJsonManager};
+  /// let mut manager = PackageJsonManager::new();
+  /// if manager.locate_closest().is_ok() && manager.read_ref().is_ok() {
+  ///   for diagnostic in manager.lint(&Linter::new()) {
+  ///     println!("{}", diagnostic);
+  ///   }
+  /// }
+  /// ```
+  pub fn lint(&self, linter: &Linter) -> Vec<Diagnostic> {
+    let keys = self.contents.as_deref().map(fs::object_keys);
+    let context = LintContext {
+      package_dir: self
+        .file_path
         .as_ref()
-        .expect("self.write_options should not be None"),
-    )
+        .and_then(|file_path| file_path.parent()),
+      keys: keys.as_deref(),
+    };
+    linter.lint(&self.json, &context)
+  }
+
+  /// Serialize the current `PackageJson` with its top-level keys in the order of the last read file, so that an untouched field keeps its place.
+  fn to_ordered_value(&self) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(&self.json).map_err(Error::Serialize)?;
+    if let Some(contents) = &self.contents {
+      fs::sort_object_keys(&mut value, &fs::object_keys(contents));
+    }
+    Ok(value)
+  }
+}
+
+/// Whether the `package.json` at `file_path` is a workspace root which includes the member in `member_dir`. An unreadable `package.json` is never a root.
+fn includes_member(file_path: &Path, member_dir: &Path) -> bool {
+  let Some(root_dir) = file_path.parent().filter(|_| file_path.is_file()) else {
+    return false;
+  };
+  let Ok((json, _)) =
+    fs::read_file(file_path).and_then(|contents| PackageJson::parse_lenient(&contents))
+  else {
+    return false;
+  };
+  let Ok((patterns, _)) = workspace::member_patterns(root_dir, json.workspaces.as_ref()) else {
+    return false;
+  };
+  let Ok(relative_dir) = member_dir.strip_prefix(root_dir) else {
+    return false;
+  };
+  let relative_path = relative_dir
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/");
+  MemberGlobs::new(&patterns).includes(&relative_path)
+}
+
+/// The error of using `PackageJsonManager` before a file path is given.
+fn not_located() -> Error {
+  Error::NotLocated {
+    filename: PACKAGE_JSON_FILENAME.to_owned(),
+    from: None,
   }
 }
 
@@ -344,3 +522,413 @@ fn test_writable() {
     assert_eq!(file_reader.version, "0.0.3");
   }
 }
+
+#[test]
+fn test_keep_keys_order() {
+  use crate::WriteOptionsBuilder;
+  use std::env::current_dir;
+  use std::fs::{read_to_string, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  let json = r#"{
+  "version": "0.0.1",
+  "foo": "bar",
+  "scripts": {
+    "test": "jest",
+    "build": "tsc",
+    "lint": "eslint ."
+  },
+  "name": "test",
+  "dependencies": {
+    "zod": "^3.0.0",
+    "axios": "^1.0.0",
+    "lodash": "^4.17.21"
+  },
+  "private": true,
+  "main": "index.js",
+  "type": "commonjs",
+  "baz": {
+    "z": 1,
+    "a": 2
+  }
+}"#;
+  write(&file_path, json).expect("write json failed");
+
+  let mut manager = PackageJsonManager::with_write_options(
+    WriteOptionsBuilder::default()
+      .build()
+      .expect("build WriteOptions failed!"),
+  );
+  manager.set_file_path(&file_path);
+  manager.read_mut().expect("read json failed");
+  manager.write().expect("write json failed");
+  assert_eq!(read_to_string(&file_path).unwrap(), json);
+}
+
+#[test]
+fn test_preserve_format() {
+  use crate::WriteOptionsBuilder;
+  use std::env::current_dir;
+  use std::fs::{read_to_string, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  let json = "{\n    \"name\":   \"test\",\n    \"version\": \"0.0.1\",\n    \"keywords\": [\"a\", \"b\"],\n    \"scripts\": {\n        \"test\": \"jest\"\n    }\n}\n";
+  write(&file_path, json).expect("write json failed");
+
+  let mut manager = PackageJsonManager::with_write_options(
+    WriteOptionsBuilder::default()
+      .preserve_format(true)
+      .build()
+      .expect("build WriteOptions failed!"),
+  );
+  manager.set_file_path(&file_path);
+
+  // no mutations
+  manager.read_mut().expect("read json failed");
+  manager.write().expect("write json failed");
+  assert_eq!(read_to_string(&file_path).unwrap(), json);
+
+  // mutate some fields
+  let json = manager.as_mut();
+  json.version = "0.0.2".to_string();
+  json.scripts.insert("build".to_string(), "tsc".to_string());
+  json.description = Some("a test package".to_string());
+  json.keywords = None;
+  manager.write().expect("write json failed");
+  assert_eq!(
+    read_to_string(&file_path).unwrap(),
+    "{\n    \"name\":   \"test\",\n    \"version\": \"0.0.2\",\n    \"scripts\": {\n        \"test\": \"jest\",\n        \"build\": \"tsc\"\n    },\n    \"description\": \"a test package\"\n}\n"
+  );
+}
+
+#[test]
+fn test_detect_format() {
+  use crate::WriteOptionsBuilder;
+  use std::env::current_dir;
+  use std::fs::{read_to_string, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  write(
+    &file_path,
+    "{\r\n\t\"name\": \"test\",\r\n\t\"version\": \"0.0.1\"\r\n}\r\n",
+  )
+  .expect("write json failed");
+
+  let mut manager = PackageJsonManager::with_write_options(
+    WriteOptionsBuilder::default()
+      .detect_format(true)
+      .build()
+      .expect("build WriteOptions failed!"),
+  );
+  manager.set_file_path(&file_path);
+  manager.read_mut().expect("read json failed").version = "0.0.2".to_string();
+  manager.write().expect("write json failed");
+  assert_eq!(
+    read_to_string(&file_path).unwrap(),
+    "{\r\n\t\"name\": \"test\",\r\n\t\"version\": \"0.0.2\",\r\n\t\"main\": \"index.js\",\r\n\t\"private\": false,\r\n\t\"type\": \"commonjs\"\r\n}\r\n"
+  );
+}
+
+#[test]
+fn test_people_format() {
+  use crate::{PeopleFormat, WriteOptionsBuilder};
+  use std::env::current_dir;
+  use std::fs::{read_to_string, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  write(
+    &file_path,
+    r#"{
+  "name": "test",
+  "version": "0.0.1",
+  "author": { "name": "Barney Rubble", "email": "b@rubble.com" },
+  "contributors": ["Fred Flintstone (http://fred.example.com/)"]
+}
+"#,
+  )
+  .expect("write json failed");
+
+  let mut manager = PackageJsonManager::with_write_options(
+    WriteOptionsBuilder::default()
+      .preserve_format(true)
+      .people_format(PeopleFormat::Literal)
+      .build()
+      .expect("build WriteOptions failed!"),
+  );
+  manager.set_file_path(&file_path);
+  manager.read_mut().expect("read json failed");
+  manager.write().expect("write json failed");
+  assert_eq!(
+    read_to_string(&file_path).unwrap(),
+    r#"{
+  "name": "test",
+  "version": "0.0.1",
+  "author": "Barney Rubble <b@rubble.com>",
+  "contributors": ["Fred Flintstone (http://fred.example.com/)"]
+}
+"#
+  );
+}
+
+#[test]
+fn test_resolve_import() {
+  use std::env::current_dir;
+  use std::fs::write;
+  use tempfile::tempdir_in;
+
+  let mut manager = PackageJsonManager::new();
+  assert!(manager.resolve_import("#utils", &[]).is_err());
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  write(
+    &file_path,
+    r##"{
+  "name": "test",
+  "version": "0.0.1",
+  "imports": {
+    "#utils/*": "./src/utils/*.js",
+    "#dep": "dep-polyfill"
+  }
+}"##,
+  )
+  .expect("write json failed");
+
+  manager.set_file_path(&file_path);
+  manager.read_ref().expect("read json failed");
+  assert_eq!(
+    manager.resolve_import("#utils/a", &[]).unwrap(),
+    ResolvedImport::Path(dir.path().join("src/utils/a.js"))
+  );
+  assert_eq!(
+    manager.resolve_import("#dep", &[]).unwrap(),
+    ResolvedImport::Package("dep-polyfill".to_owned())
+  );
+  assert!(manager.resolve_import("#missing", &[]).is_err());
+}
+
+#[test]
+fn test_bump() {
+  use crate::WriteOptionsBuilder;
+  use std::env::current_dir;
+  use std::fs::{read_to_string, write};
+  use tempfile::tempdir_in;
+
+  let mut manager = PackageJsonManager::with_write_options(
+    WriteOptionsBuilder::default()
+      .preserve_format(true)
+      .build()
+      .expect("build WriteOptions failed!"),
+  );
+  assert!(manager.bump(ReleaseType::Patch).is_err());
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  write(
+    &file_path,
+    "{\n  \"name\": \"test\",\n  \"version\": \"1.2.3\"\n}\n",
+  )
+  .expect("write json failed");
+  manager.set_file_path(&file_path);
+
+  assert_eq!(
+    manager
+      .bump(ReleaseType::Prerelease(Some("beta".to_owned())))
+      .unwrap(),
+    Version::parse("1.2.4-beta.0").unwrap()
+  );
+  assert_eq!(
+    read_to_string(&file_path).unwrap(),
+    "{\n  \"name\": \"test\",\n  \"version\": \"1.2.4-beta.0\"\n}\n"
+  );
+  assert_eq!(manager.as_ref().version, "1.2.4-beta.0");
+
+  // the unsaved edits are written along with the new version.
+  manager.as_mut().description = Some("test".to_owned());
+  assert!(manager
+    .bump(ReleaseType::Prerelease(Some("a.b".to_owned())))
+    .is_err());
+  assert_eq!(manager.as_ref().version, "1.2.4-beta.0");
+  manager.bump(ReleaseType::Patch).unwrap();
+  assert_eq!(
+    read_to_string(&file_path).unwrap(),
+    "{\n  \"name\": \"test\",\n  \"version\": \"1.2.4\",\n  \"description\": \"test\"\n}\n"
+  );
+
+  // the relocated file is read before bumping, rather than overwritten by the former one.
+  let root = dir.path().join("monorepo");
+  crate::workspace::write_package(
+    &root,
+    "",
+    r#"{ "name": "root", "version": "2.0.0", "workspaces": ["packages/*"] }"#,
+  );
+  crate::workspace::write_package(
+    &root,
+    "packages/a",
+    r#"{ "name": "a", "version": "1.0.0" }"#,
+  );
+  let member_dir = root.join("packages/a");
+  manager.locate_closest_from(&member_dir).unwrap();
+  manager.read_ref().unwrap();
+  manager.locate_workspace_root_from(&member_dir).unwrap();
+  assert_eq!(
+    manager.bump(ReleaseType::Patch).unwrap(),
+    Version::parse("2.0.1").unwrap()
+  );
+  let read_json = |file_path: PathBuf| {
+    serde_json::from_str::<serde_json::Value>(&read_to_string(file_path).unwrap()).unwrap()
+  };
+  let root_json = read_json(root.join(PACKAGE_JSON_FILENAME));
+  assert_eq!(
+    (&root_json["name"], &root_json["version"]),
+    (&"root".into(), &"2.0.1".into())
+  );
+  assert_eq!(
+    read_json(member_dir.join(PACKAGE_JSON_FILENAME))["version"],
+    "1.0.0"
+  );
+}
+
+#[test]
+fn test_read_lenient() {
+  use std::env::current_dir;
+  use std::fs::{read_to_string, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  write(
+    &file_path,
+    "{\n  \"name\": \"test\",\n  \"version\": \"1.0.0\",\n  \"bin\": 42,\n  \"files\": \"dist\"\n}\n",
+  )
+  .expect("write json failed");
+
+  let mut manager = PackageJsonManager::with_file_path(&file_path);
+  assert!(matches!(manager.read_ref(), Err(Error::Parse(_))));
+  assert_eq!(manager.read_lenient().unwrap().len(), 2);
+  assert_eq!(manager.as_ref().files, Some(vec!["dist".to_owned()]));
+
+  manager.write().unwrap();
+  let written: serde_json::Value =
+    serde_json::from_str(&read_to_string(&file_path).unwrap()).unwrap();
+  assert_eq!(written["bin"], 42);
+  assert_eq!(written["files"], serde_json::json!(["dist"]));
+}
+
+#[test]
+fn test_lint() {
+  use std::env::current_dir;
+  use std::fs::write;
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let file_path = dir.path().join(PACKAGE_JSON_FILENAME);
+  write(
+    &file_path,
+    r#"{ "name": "test", "version": "1.0.0", "private": true, "exports": "./index.mjs" }"#,
+  )
+  .expect("write json failed");
+
+  let mut manager = PackageJsonManager::with_file_path(&file_path);
+  manager.read_mut().unwrap();
+  // `main` isn't written, so the missing `index.js` isn't reported.
+  assert!(manager.lint(&Linter::new()).is_empty());
+
+  manager.as_mut().main = "lib/index.js".to_owned();
+  manager.write().unwrap();
+  manager.read_ref().unwrap();
+  assert_eq!(
+    manager
+      .lint(&Linter::new())
+      .into_iter()
+      .map(|diagnostic| diagnostic.rule)
+      .collect::<Vec<_>>(),
+    vec!["main-exists"]
+  );
+}
+
+#[test]
+fn test_locate_workspace_root() {
+  use crate::workspace::write_package;
+  use std::env::current_dir;
+  use std::fs::{create_dir_all, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let root = dir.path().join("monorepo");
+  write_package(
+    &root,
+    "",
+    r#"{ "name": "root", "version": "1.0.0", "workspaces": ["packages/*", "!packages/excluded"] }"#,
+  );
+  write_package(
+    &root,
+    "packages/a",
+    r#"{ "name": "a", "version": "1.0.0", "workspaces": ["nested/*"] }"#,
+  );
+  write_package(
+    &root,
+    "packages/a/fixtures/b",
+    r#"{ "name": "b", "version": "1.0.0" }"#,
+  );
+  write_package(
+    &root,
+    "packages/excluded",
+    r#"{ "name": "excluded", "version": "1.0.0" }"#,
+  );
+  create_dir_all(root.join("packages/a/src/utils")).unwrap();
+
+  let mut manager = PackageJsonManager::new();
+  let member = root.join("packages/a").join(PACKAGE_JSON_FILENAME);
+  let root_file = root.join(PACKAGE_JSON_FILENAME);
+  assert_eq!(
+    manager
+      .locate_workspace_root_from(root.join("packages/a/src/utils"))
+      .unwrap(),
+    (root_file.clone(), member.clone())
+  );
+  assert_eq!(manager.get_file_path(), Some(root_file.as_path()));
+
+  // the closest package.json isn't included by any workspace.
+  for (from, closest) in [
+    ("packages/a/fixtures/b", "packages/a/fixtures/b"),
+    ("packages/excluded", "packages/excluded"),
+    ("", ""),
+  ] {
+    let closest = root.join(closest).join(PACKAGE_JSON_FILENAME);
+    assert_eq!(
+      manager.locate_workspace_root_from(root.join(from)).unwrap(),
+      (closest.clone(), closest)
+    );
+  }
+
+  // pnpm-workspace.yaml takes precedence over the workspaces field.
+  write(
+    root.join(crate::PNPM_WORKSPACE_FILENAME),
+    "packages:\n  - packages/excluded\n",
+  )
+  .unwrap();
+  assert_eq!(
+    manager
+      .locate_workspace_root_from(root.join("packages/excluded"))
+      .unwrap(),
+    (
+      root_file.clone(),
+      root.join("packages/excluded").join(PACKAGE_JSON_FILENAME)
+    )
+  );
+  assert_eq!(
+    manager
+      .locate_workspace_root_from(root.join("packages/a"))
+      .unwrap(),
+    (member.clone(), member)
+  );
+}
@@ -0,0 +1,267 @@
+//! Package name validation, equivalent to [validate-npm-package-name](https://github.com/npm/validate-npm-package-name).
+
+use crate::PackageJson;
+
+/// The names which can't be used as a package name.
+const BLOCKLIST: [&str; 2] = ["node_modules", "favicon.ico"];
+
+/// The max length of a new package name.
+const MAX_LENGTH: usize = 214;
+
+/// The [built-in modules](https://nodejs.org/api/modules.html#built-in-modules) of Node.js, as `module.builtinModules` lists them. The modules which can only be loaded with the `node:` prefix, e.g. `node:test` and `node:sea`, are excluded, so `test` is a valid package name.
+pub(crate) const BUILTIN_MODULES: [&str; 54] = [
+  "assert",
+  "assert/strict",
+  "async_hooks",
+  "buffer",
+  "child_process",
+  "cluster",
+  "console",
+  "constants",
+  "crypto",
+  "dgram",
+  "diagnostics_channel",
+  "dns",
+  "dns/promises",
+  "domain",
+  "events",
+  "fs",
+  "fs/promises",
+  "http",
+  "http2",
+  "https",
+  "inspector",
+  "inspector/promises",
+  "module",
+  "net",
+  "os",
+  "path",
+  "path/posix",
+  "path/win32",
+  "perf_hooks",
+  "process",
+  "punycode",
+  "querystring",
+  "readline",
+  "readline/promises",
+  "repl",
+  "stream",
+  "stream/consumers",
+  "stream/promises",
+  "stream/web",
+  "string_decoder",
+  "sys",
+  "timers",
+  "timers/promises",
+  "tls",
+  "trace_events",
+  "tty",
+  "url",
+  "util",
+  "util/types",
+  "v8",
+  "vm",
+  "wasi",
+  "worker_threads",
+  "zlib",
+];
+
+/// The result of [validate_package_name].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameValidation {
+  /// Whether the name can be used to publish a new package, i.e. there are neither errors nor warnings.
+  pub valid_for_new_packages: bool,
+  /// Whether the name is valid for an existing package, i.e. there are no errors. Some old packages were published before the warned rules existed, e.g. `JSONStream` contains capital letters.
+  pub valid_for_old_packages: bool,
+  pub errors: Vec<String>,
+  pub warnings: Vec<String>,
+}
+
+/// Validate a package name the way npm does.
+/// ```
+/// use package_json::validate_package_name;
+///
+/// assert!(validate_package_name("@scope/some-package").valid_for_new_packages);
+///
+/// let validation = validate_package_name("JSONStream");
+/// assert!(validation.valid_for_old_packages);
+/// assert!(!validation.valid_for_new_packages);
+/// assert_eq!(validation.warnings, vec!["name can no longer contain capital letters"]);
+/// ```
+pub fn validate_package_name(name: &str) -> NameValidation {
+  let mut errors = Vec::new();
+  let mut warnings = Vec::new();
+
+  if name.is_empty() {
+    errors.push("name length must be greater than zero".to_owned());
+  }
+  if name.starts_with('.') {
+    errors.push("name cannot start with a period".to_owned());
+  }
+  if name.starts_with('_') {
+    errors.push("name cannot start with an underscore".to_owned());
+  }
+  if name.trim() != name {
+    errors.push("name cannot contain leading or trailing spaces".to_owned());
+  }
+  for blocked in BLOCKLIST {
+    if name.to_lowercase() == blocked {
+      errors.push(format!("{} is not a valid package name", blocked));
+    }
+  }
+
+  if BUILTIN_MODULES.contains(&name.to_lowercase().as_str()) {
+    warnings.push(format!("{} is a core module name", name));
+  }
+  if name.len() > MAX_LENGTH {
+    warnings.push(format!(
+      "name can no longer contain more than {} characters",
+      MAX_LENGTH
+    ));
+  }
+  if name.to_lowercase() != name {
+    warnings.push("name can no longer contain capital letters".to_owned());
+  }
+  if name
+    .rsplit('/')
+    .next()
+    .is_some_and(|name| name.contains(['~', '\'', '!', '(', ')', '*']))
+  {
+    warnings.push("name can no longer contain special characters (\"~'!()*\")".to_owned());
+  }
+
+  if !is_uri_component(name) {
+    let scoped = name
+      .strip_prefix('@')
+      .and_then(|name| name.split_once('/'))
+      .filter(|(scope, name)| !scope.is_empty() && !name.is_empty() && !name.contains('/'));
+    match scoped {
+      Some((scope, name)) => {
+        if name.starts_with('.') {
+          errors.push("name cannot start with a period".to_owned());
+        }
+        if !is_uri_component(scope) || !is_uri_component(name) {
+          errors.push("name can only contain URL-friendly characters".to_owned());
+        }
+      }
+      None => errors.push("name can only contain URL-friendly characters".to_owned()),
+    }
+  }
+
+  NameValidation {
+    valid_for_new_packages: errors.is_empty() && warnings.is_empty(),
+    valid_for_old_packages: errors.is_empty(),
+    errors,
+    warnings,
+  }
+}
+
+/// Whether `encodeURIComponent(text) === text` in JavaScript.
+fn is_uri_component(text: &str) -> bool {
+  text.chars().all(|char| {
+    char.is_ascii_alphanumeric()
+      || matches!(char, '-' | '_' | '.' | '!' | '~' | '*' | '\'' | '(' | ')')
+  })
+}
+
+/// Split a package name into the scope and the bare name, e.g. `@scope/name` is split into `Some("@scope")` and `name`.
+pub fn split_package_name(name: &str) -> (Option<&str>, &str) {
+  match name.split_once('/') {
+    Some((scope, name)) if scope.starts_with('@') => (Some(scope), name),
+    _ => (None, name),
+  }
+}
+
+impl PackageJson {
+  /// Validate the [name][PackageJson::name] field, see [validate_package_name].
+  pub fn validate_name(&self) -> NameValidation {
+    validate_package_name(&self.name)
+  }
+
+  /// The scope of the package, e.g. `@scope` of `@scope/name`.
+  pub fn scope(&self) -> Option<&str> {
+    split_package_name(&self.name).0
+  }
+
+  /// The package name without its scope, e.g. `name` of `@scope/name`.
+  pub fn bare_name(&self) -> &str {
+    split_package_name(&self.name).1
+  }
+}
+
+#[test]
+fn test_validate_package_name() {
+  for (name, new, old) in [
+    ("some-package", true, true),
+    ("example.com", true, true),
+    ("under_score", true, true),
+    ("123numeric", true, true),
+    ("@npm/thingy", true, true),
+    ("@jane/foo.js", true, true),
+    ("crazy!", false, true),
+    ("@npm-zors/money!time.js", false, true),
+    ("JSONStream", false, true),
+    ("http", false, true),
+    ("fs", false, true),
+    (&"a".repeat(215), false, true),
+    ("", false, false),
+    (".start-with-period", false, false),
+    ("@.scope/name", true, true),
+    ("@scope/.name", false, false),
+    ("@scope/.na:me", false, false),
+    ("_start-with-underscore", false, false),
+    ("contain:colons", false, false),
+    (" leading-space", false, false),
+    ("s/l/a/s/h/e/s", false, false),
+    ("node_modules", false, false),
+    ("favicon.ico", false, false),
+    ("ifyouwanttogetthesumoftwonumberswherethosetwonumbersarechosenbyfindingthelargestoftwooutofthreenumbersandsquaringthemwhichismultiplyingthembyitselfthenyoushouldinputthreenumbersintothisfunctionanditwilldothatforyou-", false, true),
+  ] {
+    let validation = validate_package_name(name);
+    assert_eq!(
+      (validation.valid_for_new_packages, validation.valid_for_old_packages),
+      (new, old),
+      "validate {:?}: {:?}",
+      name,
+      validation
+    );
+  }
+
+  assert_eq!(
+    validate_package_name("_start").errors,
+    vec!["name cannot start with an underscore"]
+  );
+  assert_eq!(
+    validate_package_name("@scope/.na:me").errors,
+    vec![
+      "name cannot start with a period",
+      "name can only contain URL-friendly characters"
+    ]
+  );
+  assert_eq!(
+    validate_package_name("fs").warnings,
+    vec!["fs is a core module name"]
+  );
+  // the modules only available with the `node:` prefix aren't core module names.
+  for name in ["test", "sea"] {
+    assert!(
+      validate_package_name(name).valid_for_new_packages,
+      "{}",
+      name
+    );
+  }
+}
+
+#[test]
+fn test_split_package_name() {
+  let mut json = PackageJson {
+    name: "@scope/name".to_owned(),
+    ..Default::default()
+  };
+  assert_eq!(json.scope(), Some("@scope"));
+  assert_eq!(json.bare_name(), "name");
+
+  json.name = "name".to_owned();
+  assert_eq!(json.scope(), None);
+  assert_eq!(json.bare_name(), "name");
+}
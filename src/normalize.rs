@@ -0,0 +1,586 @@
+//! The normalization which npm runs before publishing, equivalent to [normalize-package-data](https://github.com/npm/normalize-package-data).
+
+use crate::name::BUILTIN_MODULES;
+use crate::{
+  validate_license, DependencySpec, HostedGitInfo, PackageBin, PackageBugs, PackageBugsRecord,
+  PackageJson, PackageLicense, PackageMan, PackageRepository, PackageRepositoryRecord,
+  PeopleFormat, Version,
+};
+use indexmap::IndexMap;
+use serde_json::Value;
+
+/// The top-level fields which are probably misspelled.
+const TYPOS: [(&str, &str); 19] = [
+  ("dependancies", "dependencies"),
+  ("dependecies", "dependencies"),
+  ("depdenencies", "dependencies"),
+  ("devEependencies", "devDependencies"),
+  ("depends", "dependencies"),
+  ("dev-dependencies", "devDependencies"),
+  ("devDependences", "devDependencies"),
+  ("devDepenencies", "devDependencies"),
+  ("devdependencies", "devDependencies"),
+  ("repostitory", "repository"),
+  ("repo", "repository"),
+  ("prefereGlobal", "preferGlobal"),
+  ("hompage", "homepage"),
+  ("hampage", "homepage"),
+  ("autohr", "author"),
+  ("autor", "author"),
+  ("contributers", "contributors"),
+  ("publicationConfig", "publishConfig"),
+  ("script", "scripts"),
+];
+
+/// The scripts which are probably misspelled.
+const SCRIPT_TYPOS: [(&str, &str); 2] = [("server", "start"), ("tests", "test")];
+
+impl PackageJson {
+  /// Normalize the package as npm does before publishing, and return the warnings in the same order and wording as npm. The warnings are always empty for a [private][PackageJson::private] package, but it's normalized all the same.
+  ///
+  /// It fills `bugs` and `homepage` from `repository`, expands the repository and dependency shorthands, moves `repositories`, `bundledDependencies` and `optionalDependencies` to where npm expects them, drops the deprecated `modules` field, converts the people into the object form and fixes the shape of `bin` and `man`.
+  ///
+  /// An invalid name or version, which npm rejects with an error, is left untouched, see [validate_name][PackageJson::validate_name] and [parse_version][PackageJson::parse_version].
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let mut json: PackageJson = serde_json::from_str(r#"{
+  ///   "name": "test",
+  ///   "version": "v1.0.0",
+  ///   "repository": "npm/cli"
+  /// }"#).unwrap();
+  /// let warnings = json.normalize();
+  /// assert_eq!(json.version, "1.0.0");
+  /// assert_eq!(json.homepage.as_deref(), Some("https://github.com/npm/cli#readme"));
+  /// assert_eq!(warnings, vec!["No description", "No README data", "No license field."]);
+  /// ```
+  pub fn normalize(&mut self) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    self.normalize_name(&mut warnings);
+    self.normalize_version();
+    if self.description.as_deref().is_none_or(str::is_empty) {
+      warnings.push("No description".to_owned());
+    }
+    self.normalize_repository(&mut warnings);
+    if self.unknowns.shift_remove("modules").is_some() {
+      warnings.push("modules field is deprecated".to_owned());
+    }
+    self.normalize_scripts(&mut warnings);
+    self.normalize_files(&mut warnings);
+    self.normalize_bin();
+    if let Some(PackageMan::Literal(man)) = &self.man {
+      self.man = Some(PackageMan::Slice(vec![man.clone()]));
+    }
+    self.normalize_bugs(&mut warnings);
+    if let Some(keywords) = self.keywords.as_mut() {
+      let len = keywords.len();
+      keywords.retain(|keyword| !keyword.is_empty());
+      for _ in keywords.len()..len {
+        warnings.push("keywords should be an array of strings".to_owned());
+      }
+    }
+    if self
+      .unknowns
+      .get("readme")
+      .and_then(|readme| readme.as_str())
+      .is_none_or(str::is_empty)
+    {
+      warnings.push("No README data".to_owned());
+      self.unknowns.insert(
+        "readme".to_owned(),
+        Value::String("ERROR: No README data found!".to_owned()),
+      );
+    }
+    self.normalize_homepage();
+    self.normalize_license_field(&mut warnings);
+    self.normalize_dependencies(&mut warnings);
+    self.normalize_people(PeopleFormat::Record);
+    for (typo, field) in TYPOS {
+      if self.unknowns.contains_key(typo) {
+        warnings.push(format!("{} should probably be {}.", typo, field));
+      }
+    }
+
+    if self.private {
+      warnings.clear();
+    }
+    warnings
+  }
+
+  fn normalize_name(&mut self, warnings: &mut Vec<String>) {
+    self.name = self.name.trim().to_owned();
+    if BUILTIN_MODULES.contains(&self.name.as_str()) {
+      warnings.push(format!(
+        "{} is also the name of a node core module.",
+        self.name
+      ));
+    }
+  }
+
+  /// Clean a loose version as `semver.clean` does, e.g. `=v1.0.0 ` becomes `1.0.0`.
+  fn normalize_version(&mut self) {
+    let version = self.version.trim().trim_start_matches(['=', 'v']);
+    if let Ok(version) = Version::parse(version) {
+      self.version = version.to_string();
+    }
+  }
+
+  fn normalize_repository(&mut self, warnings: &mut Vec<String>) {
+    if let Some(repositories) = self.unknowns.shift_remove("repositories") {
+      warnings.push(
+        "'repositories' (plural) Not supported. Please pick one as the 'repository' field"
+          .to_owned(),
+      );
+      if let Some(repository) = repositories
+        .as_array()
+        .and_then(|repositories| repositories.first())
+        .and_then(|repository| serde_json::from_value(repository.clone()).ok())
+      {
+        self.repository = Some(repository);
+      }
+    }
+
+    let mut record = match self.repository.take() {
+      None => {
+        warnings.push("No repository field.".to_owned());
+        return;
+      }
+      Some(PackageRepository::Url(url)) => PackageRepositoryRecord {
+        r#type: "git".to_owned(),
+        url,
+        directory: None,
+      },
+      Some(PackageRepository::Record(record)) => record,
+    };
+    if let Some(info) = HostedGitInfo::parse(&record.url) {
+      let url = record.url.strip_prefix("git+").unwrap_or(&record.url);
+      if url.starts_with("ssh://") || !url.contains("://") && url.contains('@') {
+        record.url = info.ssh_url();
+      } else if !url.starts_with("git://") {
+        record.url = info.https_url();
+      }
+    }
+    if record.url.contains("github.com/") && record.url.ends_with(".git.git") {
+      warnings.push(format!("Probably broken git url: {}", record.url));
+    }
+    self.repository = Some(PackageRepository::Record(record));
+  }
+
+  fn normalize_scripts(&mut self, warnings: &mut Vec<String>) {
+    for (typo, script) in SCRIPT_TYPOS {
+      if self.scripts.contains_key(typo) && !self.scripts.contains_key(script) {
+        warnings.push(format!(
+          "scripts['{}'] should probably be scripts['{}'].",
+          typo, script
+        ));
+      }
+    }
+  }
+
+  fn normalize_files(&mut self, warnings: &mut Vec<String>) {
+    if let Some(files) = self.files.as_mut() {
+      let len = files.len();
+      files.retain(|file| !file.is_empty());
+      for _ in files.len()..len {
+        warnings.push("Invalid filename in 'files' list: ".to_owned());
+      }
+    }
+  }
+
+  fn normalize_bin(&mut self) {
+    if let Some(PackageBin::Literal(bin)) = &self.bin {
+      self.bin = Some(PackageBin::Record(IndexMap::from([(
+        self.bare_name().to_owned(),
+        bin.clone(),
+      )])));
+    }
+  }
+
+  fn normalize_bugs(&mut self, warnings: &mut Vec<String>) {
+    let bugs = match self.bugs.take() {
+      None => {
+        self.bugs = self.hosted_git_info().map(|info| {
+          PackageBugs::Record(PackageBugsRecord {
+            url: Some(info.bugs_url()),
+            email: None,
+          })
+        });
+        return;
+      }
+      Some(PackageBugs::Url(bugs)) => {
+        if is_email(&bugs) {
+          PackageBugsRecord {
+            url: None,
+            email: Some(bugs),
+          }
+        } else if has_protocol(&bugs) {
+          PackageBugsRecord {
+            url: Some(bugs),
+            email: None,
+          }
+        } else {
+          warnings.push("Bug string field must be url, email, or {email,url}".to_owned());
+          PackageBugsRecord::default()
+        }
+      }
+      Some(PackageBugs::Record(PackageBugsRecord { url, email })) => PackageBugsRecord {
+        url: url.filter(|url| {
+          let valid = has_protocol(url);
+          if !valid {
+            warnings.push("bugs.url field must be a string url. Deleted.".to_owned());
+          }
+          valid
+        }),
+        email: email.filter(|email| {
+          let valid = is_email(email);
+          if !valid {
+            warnings.push("bugs.email field must be a string email. Deleted.".to_owned());
+          }
+          valid
+        }),
+      },
+    };
+    if bugs.url.is_none() && bugs.email.is_none() {
+      warnings.push("Normalized value of bugs field is an empty object. Deleted.".to_owned());
+    } else {
+      self.bugs = Some(PackageBugs::Record(bugs));
+    }
+  }
+
+  fn normalize_homepage(&mut self) {
+    if self.homepage.as_deref().is_none_or(str::is_empty) {
+      self.homepage = self.hosted_git_info().map(|info| info.docs_url());
+    }
+    if let Some(homepage) = self.homepage.as_mut() {
+      if !has_protocol(homepage) {
+        *homepage = format!("http://{}", homepage);
+      }
+    }
+  }
+
+  fn normalize_license_field(&mut self, warnings: &mut Vec<String>) {
+    let license = match &self.license {
+      Some(license) => Some(license.clone()),
+      None => self
+        .unknowns
+        .get("licence")
+        .and_then(|licence| serde_json::from_value::<PackageLicense>(licence.clone()).ok()),
+    };
+    match license {
+      None => warnings.push("No license field.".to_owned()),
+      Some(PackageLicense::Expression(license))
+        if validate_license(&license).valid_for_new_packages => {}
+      Some(_) => warnings.push("license should be a valid SPDX license expression".to_owned()),
+    }
+  }
+
+  fn normalize_dependencies(&mut self, warnings: &mut Vec<String>) {
+    if let Some(optional_dependencies) = &self.optional_dependencies {
+      let dependencies = self.dependencies.get_or_insert_with(Default::default);
+      for (name, spec) in optional_dependencies {
+        dependencies.insert(name.clone(), spec.clone());
+      }
+    }
+
+    // npm takes `bundleDependencies` as the canonical spelling.
+    if !self.unknowns.contains_key("bundleDependencies") {
+      if let Some(bundled_dependencies) = self.bundled_dependencies.take() {
+        self
+          .unknowns
+          .insert("bundleDependencies".to_owned(), bundled_dependencies.into());
+      }
+    }
+    match self.unknowns.get_mut("bundleDependencies") {
+      None => {}
+      Some(Value::Array(bundle_dependencies)) => {
+        bundle_dependencies.retain(|name| match name.as_str() {
+          Some(name) if !name.is_empty() => true,
+          _ => {
+            warnings.push(format!(
+              "Invalid bundleDependencies member: {}",
+              name
+                .as_str()
+                .map_or_else(|| name.to_string(), str::to_owned)
+            ));
+            false
+          }
+        });
+        for name in bundle_dependencies.iter().filter_map(Value::as_str) {
+          let dependencies = self.dependencies.get_or_insert_with(Default::default);
+          if !dependencies.contains_key(name) {
+            warnings.push(format!("Non-dependency in bundleDependencies: {}", name));
+            dependencies.insert(name.to_owned(), "*".to_owned());
+          }
+        }
+      }
+      Some(_) => {
+        warnings
+          .push("Invalid 'bundleDependencies' list. Must be array of package names".to_owned());
+        self.unknowns.shift_remove("bundleDependencies");
+      }
+    }
+
+    for dependencies in [self.dependencies.as_mut(), self.dev_dependencies.as_mut()]
+      .into_iter()
+      .flatten()
+    {
+      for spec in dependencies.values_mut() {
+        if let Ok(DependencySpec::Hosted { .. }) = DependencySpec::parse(spec) {
+          if let Some(info) = HostedGitInfo::parse(spec) {
+            *spec = info.shortcut();
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Whether `text` looks like an email as npm checks.
+fn is_email(text: &str) -> bool {
+  match (text.find('@'), text.rfind('.')) {
+    (Some(at), Some(dot)) => at < dot,
+    _ => false,
+  }
+}
+
+/// Whether `url` starts with a protocol like `https:` or `mailto:`.
+fn has_protocol(url: &str) -> bool {
+  url.split_once(':').is_some_and(|(protocol, _)| {
+    !protocol.is_empty()
+      && protocol
+        .chars()
+        .all(|char| char.is_ascii_alphanumeric() || matches!(char, '.' | '+' | '-'))
+  })
+}
+
+#[test]
+fn test_normalize() {
+  let mut json = serde_json::from_str::<PackageJson>(
+    r##"{
+      "name": " @scope/test ",
+      "version": "=v1.2.3",
+      "description": "test",
+      "repositories": ["git@github.com:npm/cli.git", "npm/other"],
+      "modules": { "a": "a.js" },
+      "scripts": { "server": "node server.js", "tests": "jest", "test": "jest" },
+      "files": ["dist", ""],
+      "bin": "./bin/test.js",
+      "man": "./man/test.1",
+      "keywords": ["a", ""],
+      "readme": "# test",
+      "license": "MIT",
+      "author": "Barney Rubble <b@rubble.com>",
+      "dependencies": { "a": "^1.0.0", "b": "npm/b#v1" },
+      "optionalDependencies": { "c": "^2.0.0" },
+      "bundleDependencies": ["a", "d"],
+      "dependancies": {}
+    }"##,
+  )
+  .unwrap();
+
+  assert_eq!(
+    json.normalize(),
+    vec![
+      "'repositories' (plural) Not supported. Please pick one as the 'repository' field",
+      "modules field is deprecated",
+      "scripts['server'] should probably be scripts['start'].",
+      "Invalid filename in 'files' list: ",
+      "keywords should be an array of strings",
+      "Non-dependency in bundleDependencies: d",
+      "dependancies should probably be dependencies.",
+    ]
+  );
+  assert_eq!(json.name, "@scope/test");
+  assert_eq!(json.version, "1.2.3");
+  assert_eq!(
+    json.repository,
+    Some(PackageRepository::Record(PackageRepositoryRecord {
+      r#type: "git".to_owned(),
+      url: "git+ssh://git@github.com/npm/cli.git".to_owned(),
+      directory: None,
+    }))
+  );
+  assert!(!json.unknowns.contains_key("repositories"));
+  assert!(!json.unknowns.contains_key("modules"));
+  assert_eq!(json.files, Some(vec!["dist".to_owned()]));
+  assert_eq!(
+    json.bin,
+    Some(PackageBin::Record(IndexMap::from([(
+      "test".to_owned(),
+      "./bin/test.js".to_owned()
+    )])))
+  );
+  assert_eq!(
+    json.man,
+    Some(PackageMan::Slice(vec!["./man/test.1".to_owned()]))
+  );
+  assert_eq!(
+    json.bugs,
+    Some(PackageBugs::Record(PackageBugsRecord {
+      url: Some("https://github.com/npm/cli/issues".to_owned()),
+      email: None,
+    }))
+  );
+  assert_eq!(
+    json.homepage.as_deref(),
+    Some("https://github.com/npm/cli#readme")
+  );
+  assert_eq!(
+    serde_json::to_value(&json.dependencies).unwrap(),
+    serde_json::json!({ "a": "^1.0.0", "b": "github:npm/b#v1", "c": "^2.0.0", "d": "*" })
+  );
+  assert_eq!(
+    json.unknowns["bundleDependencies"],
+    serde_json::json!(["a", "d"])
+  );
+  assert_eq!(json.bundled_dependencies, None);
+  assert_eq!(json.unknowns["readme"], "# test");
+  assert_eq!(
+    serde_json::to_value(&json.author).unwrap(),
+    serde_json::json!({ "name": "Barney Rubble", "email": "b@rubble.com" })
+  );
+}
+
+#[test]
+fn test_normalize_warnings() {
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "http",
+      "version": "1.0.0",
+      "bugs": "not a url",
+      "homepage": "example.com",
+      "license": "Apache 2",
+      "repository": "git+https://github.com/npm/cli.git.git"
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(
+    json.normalize(),
+    vec![
+      "http is also the name of a node core module.",
+      "No description",
+      "Probably broken git url: git+https://github.com/npm/cli.git.git",
+      "Bug string field must be url, email, or {email,url}",
+      "Normalized value of bugs field is an empty object. Deleted.",
+      "No README data",
+      "license should be a valid SPDX license expression",
+    ]
+  );
+  assert_eq!(json.bugs, None);
+  assert_eq!(json.homepage.as_deref(), Some("http://example.com"));
+  assert_eq!(json.unknowns["readme"], "ERROR: No README data found!");
+
+  json.private = true;
+  assert!(json.normalize().is_empty());
+
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "test",
+      "version": "1.0.0",
+      "bugs": { "url": "github.com/npm/cli/issues", "email": "b@rubble.com" }
+    }"#,
+  )
+  .unwrap();
+  let warnings = json.normalize();
+  assert!(warnings.contains(&"bugs.url field must be a string url. Deleted.".to_owned()));
+  assert!(warnings.contains(&"No repository field.".to_owned()));
+  assert_eq!(
+    json.bugs,
+    Some(PackageBugs::Record(PackageBugsRecord {
+      url: None,
+      email: Some("b@rubble.com".to_owned()),
+    }))
+  );
+}
+
+#[test]
+fn test_normalize_bundle_dependencies() {
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "test",
+      "version": "1.0.0",
+      "dependencies": { "a": "^1.0.0" },
+      "bundledDependencies": ["a"]
+    }"#,
+  )
+  .unwrap();
+  json.normalize();
+  assert_eq!(json.bundled_dependencies, None);
+  assert_eq!(
+    json.unknowns["bundleDependencies"],
+    serde_json::json!(["a"])
+  );
+
+  // `bundledDependencies` is kept as it is when `bundleDependencies` is present.
+  let mut json = serde_json::from_str::<PackageJson>(
+    r##"{
+      "name": "test",
+      "version": "1.0.0",
+      "description": "test",
+      "readme": "# test",
+      "license": "MIT",
+      "repository": "npm/cli",
+      "dependencies": { "a": "^1.0.0", "b": "^1.0.0" },
+      "bundledDependencies": ["a"],
+      "bundleDependencies": ["b", "", 1]
+    }"##,
+  )
+  .unwrap();
+  assert_eq!(
+    json.normalize(),
+    vec![
+      "Invalid bundleDependencies member: ",
+      "Invalid bundleDependencies member: 1",
+    ]
+  );
+  assert_eq!(json.bundled_dependencies, Some(vec!["a".to_owned()]));
+  assert_eq!(
+    json.unknowns["bundleDependencies"],
+    serde_json::json!(["b"])
+  );
+
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{ "name": "test", "version": "1.0.0", "bundleDependencies": "a" }"#,
+  )
+  .unwrap();
+  assert!(json
+    .normalize()
+    .contains(&"Invalid 'bundleDependencies' list. Must be array of package names".to_owned()));
+  assert!(!json.unknowns.contains_key("bundleDependencies"));
+}
+
+#[test]
+fn test_normalize_git_suffixed_shorthand() {
+  let mut json = serde_json::from_str::<PackageJson>(
+    r##"{
+      "name": "test",
+      "version": "1.0.0",
+      "description": "test",
+      "readme": "# test",
+      "license": "MIT",
+      "repository": "npm/cli.git"
+    }"##,
+  )
+  .unwrap();
+  assert!(json.normalize().is_empty());
+  assert_eq!(
+    json.repository,
+    Some(PackageRepository::Record(PackageRepositoryRecord {
+      r#type: "git".to_owned(),
+      url: "git+https://github.com/npm/cli.git".to_owned(),
+      directory: None,
+    }))
+  );
+  assert_eq!(
+    json.bugs,
+    Some(PackageBugs::Record(PackageBugsRecord {
+      url: Some("https://github.com/npm/cli/issues".to_owned()),
+      email: None,
+    }))
+  );
+  assert_eq!(
+    json.homepage.as_deref(),
+    Some("https://github.com/npm/cli#readme")
+  );
+}
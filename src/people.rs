@@ -0,0 +1,227 @@
+//! The [people fields](https://docs.npmjs.com/cli/v10/configuring-npm/package-json#people-fields-author-contributors) `author`, `contributors` and `maintainers`.
+
+use crate::fs::write_options::PeopleFormat;
+use crate::{PackageJson, PackagePeople, PackagePeopleRecord};
+use std::fmt;
+use std::str::FromStr;
+
+impl PackagePeopleRecord {
+  /// Parse the shorthand string of a person, `email` and `url` are both optional. It never fails as npm does, the missing parts are left empty. The record is formatted in the order of `name <email> (url)`, keep the [PackagePeople::Literal] to preserve the original string.
+  /// ```
+  /// use package_json::PackagePeopleRecord;
+  ///
+  /// let person = PackagePeopleRecord::parse("Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)");
+  /// assert_eq!(person.name, "Barney Rubble");
+  /// assert_eq!(person.email.as_deref(), Some("b@rubble.com"));
+  /// assert_eq!(person.url.as_deref(), Some("http://barnyrubble.tumblr.com/"));
+  /// assert_eq!(person.to_string(), "Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)");
+  /// ```
+  pub fn parse(person: &str) -> Self {
+    let name = person
+      .split(['(', '<'])
+      .next()
+      .unwrap_or_default()
+      .trim()
+      .to_owned();
+    Self {
+      name,
+      email: enclosed(person, '<', '>').map(str::to_owned),
+      url: enclosed(person, '(', ')').map(str::to_owned),
+    }
+  }
+}
+
+/// Find the first non-empty text enclosed by `open` and `close`, which contains neither of them.
+fn enclosed(text: &str, open: char, close: char) -> Option<&str> {
+  text.match_indices(open).find_map(|(start, _)| {
+    let rest = &text[start + open.len_utf8()..];
+    let end = rest.find([open, close])?;
+    (end > 0 && rest[end..].starts_with(close)).then(|| &rest[..end])
+  })
+}
+
+impl fmt::Display for PackagePeopleRecord {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut parts = Vec::new();
+    if !self.name.is_empty() {
+      parts.push(self.name.clone());
+    }
+    if let Some(email) = &self.email {
+      parts.push(format!("<{}>", email));
+    }
+    if let Some(url) = &self.url {
+      parts.push(format!("({})", url));
+    }
+    write!(f, "{}", parts.join(" "))
+  }
+}
+
+impl FromStr for PackagePeopleRecord {
+  type Err = std::convert::Infallible;
+
+  fn from_str(person: &str) -> Result<Self, Self::Err> {
+    Ok(Self::parse(person))
+  }
+}
+
+impl PackagePeople {
+  /// The person in the object form, a shorthand string is parsed by [PackagePeopleRecord::parse].
+  pub fn to_record(&self) -> PackagePeopleRecord {
+    match self {
+      Self::Literal(literal) => PackagePeopleRecord::parse(literal),
+      Self::Record(record) => record.clone(),
+    }
+  }
+
+  /// The person in the shorthand string form.
+  pub fn to_literal(&self) -> String {
+    match self {
+      Self::Literal(literal) => literal.clone(),
+      Self::Record(record) => record.to_string(),
+    }
+  }
+
+  /// Convert the person into the given `format`. A person already in `format` is kept as it is, so that a shorthand string is never rewritten into [PeopleFormat::Literal].
+  pub fn to_format(&self, format: PeopleFormat) -> Self {
+    match format {
+      PeopleFormat::Literal => Self::Literal(self.to_literal()),
+      PeopleFormat::Record => Self::Record(self.to_record()),
+    }
+  }
+}
+
+impl PackageJson {
+  /// Convert all entries of [author][PackageJson::author], [contributors][PackageJson::contributors] and [maintainers][PackageJson::maintainers] into the same `format`.
+  pub fn normalize_people(&mut self, format: PeopleFormat) {
+    if let Some(author) = self.author.as_mut() {
+      *author = author.to_format(format);
+    }
+    for people in [self.contributors.as_mut(), self.maintainers.as_mut()]
+      .into_iter()
+      .flatten()
+    {
+      for person in people.iter_mut() {
+        *person = person.to_format(format);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_parse_people() {
+  for (person, name, email, url) in [
+    (
+      "Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)",
+      "Barney Rubble",
+      Some("b@rubble.com"),
+      Some("http://barnyrubble.tumblr.com/"),
+    ),
+    ("Barney Rubble", "Barney Rubble", None, None),
+    (
+      "Barney Rubble <b@rubble.com>",
+      "Barney Rubble",
+      Some("b@rubble.com"),
+      None,
+    ),
+    (
+      "Barney Rubble (http://barnyrubble.tumblr.com/)",
+      "Barney Rubble",
+      None,
+      Some("http://barnyrubble.tumblr.com/"),
+    ),
+    (
+      "Barney Rubble (http://barnyrubble.tumblr.com/) <b@rubble.com>",
+      "Barney Rubble",
+      Some("b@rubble.com"),
+      Some("http://barnyrubble.tumblr.com/"),
+    ),
+    ("<b@rubble.com>", "", Some("b@rubble.com"), None),
+    ("  Barney Rubble  <>  ()", "Barney Rubble", None, None),
+  ] {
+    let record = PackagePeopleRecord::parse(person);
+    assert_eq!(record.name, name, "{:?}", person);
+    assert_eq!(record.email.as_deref(), email, "{:?}", person);
+    assert_eq!(record.url.as_deref(), url, "{:?}", person);
+  }
+}
+
+#[test]
+fn test_format_people() {
+  for person in [
+    "Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)",
+    "Barney Rubble <b@rubble.com>",
+    "Barney Rubble (http://barnyrubble.tumblr.com/)",
+    "Barney Rubble",
+    "<b@rubble.com>",
+  ] {
+    assert_eq!(PackagePeopleRecord::parse(person).to_string(), person);
+  }
+
+  // a shorthand string is kept losslessly until it's converted into a record.
+  for person in [
+    "Barney Rubble (http://barnyrubble.tumblr.com/) <b@rubble.com>",
+    "  Barney Rubble  <>  ()",
+    "Barney   Rubble<b@rubble.com>",
+  ] {
+    let literal = PackagePeople::Literal(person.to_owned());
+    assert_eq!(literal.to_literal(), person);
+    assert_eq!(literal.to_format(PeopleFormat::Literal), literal);
+  }
+  assert_eq!(
+    PackagePeople::Literal(
+      "Barney Rubble (http://barnyrubble.tumblr.com/) <b@rubble.com>".to_owned()
+    )
+    .to_format(PeopleFormat::Record)
+    .to_literal(),
+    "Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)"
+  );
+}
+
+#[test]
+fn test_normalize_people() {
+  let mut json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "test",
+      "version": "1.0.0",
+      "author": "Barney Rubble <b@rubble.com>",
+      "contributors": [
+        { "name": "Fred Flintstone", "url": "http://fred.example.com/" },
+        "Wilma Flintstone"
+      ]
+    }"#,
+  )
+  .unwrap();
+
+  json.normalize_people(PeopleFormat::Record);
+  assert_eq!(
+    json.author,
+    Some(PackagePeople::Record(PackagePeopleRecord {
+      name: "Barney Rubble".to_owned(),
+      email: Some("b@rubble.com".to_owned()),
+      url: None,
+    }))
+  );
+  assert_eq!(
+    serde_json::to_value(&json.contributors).unwrap(),
+    serde_json::json!([
+      { "name": "Fred Flintstone", "url": "http://fred.example.com/" },
+      { "name": "Wilma Flintstone" }
+    ])
+  );
+
+  json.normalize_people(PeopleFormat::Literal);
+  assert_eq!(
+    json.author,
+    Some(PackagePeople::Literal(
+      "Barney Rubble <b@rubble.com>".to_owned()
+    ))
+  );
+  assert_eq!(
+    json.contributors,
+    Some(vec![
+      PackagePeople::Literal("Fred Flintstone (http://fred.example.com/)".to_owned()),
+      PackagePeople::Literal("Wilma Flintstone".to_owned()),
+    ])
+  );
+  assert_eq!(json.maintainers, None);
+}
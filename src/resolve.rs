@@ -0,0 +1,501 @@
+//! An implementation of the [Node.js package resolution algorithm](https://nodejs.org/api/esm.html#resolution-algorithm-specification) over a `package.json`.
+
+use crate::error::{Error, Result};
+use crate::{PackageExports, PackageExportsTarget, PackageJson};
+use indexmap::IndexMap;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// A `#` specifier resolved by [PackageJsonManager::resolve_import](crate::PackageJsonManager::resolve_import).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedImport {
+  /// A file of the package, joined with the directory of the located `package.json` file.
+  Path(PathBuf),
+  /// A bare specifier of another package, e.g. `"#dep": "dep-polyfill"`.
+  Package(String),
+}
+
+impl ResolvedImport {
+  /// Join a target returned by [PackageJson::resolve_import] with the package directory.
+  pub(crate) fn new(target: String, package_dir: &Path) -> Self {
+    match target.strip_prefix("./") {
+      Some(path) => Self::Path(package_dir.join(path)),
+      None => Self::Package(target),
+    }
+  }
+}
+
+/// The result of resolving a target. A missing result (`None`) stands for `undefined` in the Node.js specification, which lets the resolution continue with the next condition.
+enum Resolved {
+  Target(String),
+  /// A `null` target, which stops the resolution.
+  Null,
+}
+
+enum TargetError {
+  InvalidPackageTarget(String),
+  InvalidModuleSpecifier(String),
+}
+
+impl From<TargetError> for Error {
+  fn from(error: TargetError) -> Self {
+    match error {
+      TargetError::InvalidPackageTarget(target) => Error::Validation(format!(
+        "[ERR_INVALID_PACKAGE_TARGET] Invalid package target \"{}\".",
+        target
+      )),
+      TargetError::InvalidModuleSpecifier(specifier) => Error::Validation(format!(
+        "[ERR_INVALID_MODULE_SPECIFIER] Invalid module specifier \"{}\".",
+        specifier
+      )),
+    }
+  }
+}
+
+impl PackageJson {
+  /// Resolve a bare specifier of this package, e.g. `pkg` or `pkg/sub/path`, to a file path relative to the package root under the given active `conditions`.
+  ///
+  /// The `"default"` condition always matches. It follows the `exports` field when it's present, otherwise it falls back to `browser` (with the `"browser"` condition), `module` (with the `"import"` or `"module"` condition) and [main][PackageJson::main] for the package root.
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let json = serde_json::from_str::<PackageJson>(r#"{
+  ///   "name": "pkg",
+  ///   "version": "1.0.0",
+  ///   "exports": {
+  ///     ".": { "import": "./index.mjs", "require": "./index.cjs" },
+  ///     "./feature/*": "./src/feature/*.js"
+  ///   }
+  /// }"#).unwrap();
+  /// assert_eq!(json.resolve("pkg", &["node", "import"]).unwrap(), "./index.mjs");
+  /// assert_eq!(json.resolve("pkg/feature/a", &["node", "import"]).unwrap(), "./src/feature/a.js");
+  /// assert!(json.resolve("pkg/package.json", &["node", "import"]).is_err());
+  /// ```
+  pub fn resolve(&self, specifier: &str, conditions: &[&str]) -> Result<String> {
+    let (name, subpath) = split_specifier(specifier)?;
+    if name != self.name {
+      return Err(Error::Validation(format!(
+        "Couldn't resolve \"{}\" from package \"{}\".",
+        specifier, self.name
+      )));
+    }
+    self.resolve_export(&subpath, conditions)
+  }
+
+  /// Resolve a subpath of this package, e.g. `.` or `./sub/path`, to a file path relative to the package root. See [resolve][PackageJson::resolve].
+  pub fn resolve_export(&self, subpath: &str, conditions: &[&str]) -> Result<String> {
+    match &self.exports {
+      Some(exports) => {
+        let resolved = match exports {
+          PackageExports::Main(target) if subpath == "." => {
+            resolve_target(target, None, false, conditions)?
+          }
+          PackageExports::Subpaths(subpaths) if subpath == "." => match subpaths.get(".") {
+            Some(target) => resolve_target(target, None, false, conditions)?,
+            None => None,
+          },
+          PackageExports::Subpaths(subpaths) => {
+            resolve_match_key(subpath, subpaths, false, conditions)?
+          }
+          PackageExports::Main(_) => None,
+        };
+        match resolved {
+          Some(Resolved::Target(target)) => Ok(target),
+          _ => Err(Error::Validation(format!(
+            "[ERR_PACKAGE_PATH_NOT_EXPORTED] Package subpath \"{}\" is not defined by \"exports\" in package \"{}\".",
+            subpath,
+            self.name
+          ))),
+        }
+      }
+      None if subpath == "." => Ok(relative_path(&self.legacy_main(conditions))),
+      None => Ok(relative_path(subpath)),
+    }
+  }
+
+  /// Resolve a private `#` specifier of this package, e.g. `#utils/path`, by the `imports` field under the given active `conditions`.
+  ///
+  /// The result is either a file path relative to the package root, e.g. `./src/utils/path.js`, or a bare specifier of another package.
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let json = serde_json::from_str::<PackageJson>(r##"{
+  ///   "name": "pkg",
+  ///   "version": "1.0.0",
+  ///   "imports": {
+  ///     "#utils/*": { "node": "./src/utils/*.js", "default": "./src/utils/*.browser.js" },
+  ///     "#dep": "dep-polyfill"
+  ///   }
+  /// }"##).unwrap();
+  /// assert_eq!(json.resolve_import("#utils/path", &["node"]).unwrap(), "./src/utils/path.js");
+  /// assert_eq!(json.resolve_import("#dep", &["node"]).unwrap(), "dep-polyfill");
+  /// ```
+  pub fn resolve_import(&self, specifier: &str, conditions: &[&str]) -> Result<String> {
+    if specifier == "#" || specifier.starts_with("#/") || !specifier.starts_with('#') {
+      return Err(Error::Validation(format!(
+        "[ERR_INVALID_MODULE_SPECIFIER] Invalid module specifier \"{}\".",
+        specifier
+      )));
+    }
+    let resolved = match &self.imports {
+      Some(imports) => resolve_match_key(specifier, imports, true, conditions)?,
+      None => None,
+    };
+    match resolved {
+      Some(Resolved::Target(target)) => Ok(target),
+      _ => Err(Error::Validation(format!(
+        "[ERR_PACKAGE_IMPORT_NOT_DEFINED] Package import specifier \"{}\" is not defined in package \"{}\".",
+        specifier,
+        self.name
+      ))),
+    }
+  }
+
+  /// The entry point of the package root when the `exports` field is absent.
+  fn legacy_main(&self, conditions: &[&str]) -> String {
+    if conditions.contains(&"browser") {
+      if let Some(browser) = &self.browser {
+        return browser.to_owned();
+      }
+    }
+    if conditions.contains(&"import") || conditions.contains(&"module") {
+      if let Some(module) = self
+        .unknowns
+        .get("module")
+        .and_then(|module| module.as_str())
+      {
+        return module.to_owned();
+      }
+    }
+    self.main.to_owned()
+  }
+}
+
+/// Split a bare specifier into the package name and the subpath, e.g. `@scope/pkg/sub` is split into `@scope/pkg` and `./sub`.
+fn split_specifier(specifier: &str) -> Result<(&str, String)> {
+  let name_length = if specifier.starts_with('@') {
+    specifier.find('/').map(|scope_end| {
+      specifier[scope_end + 1..]
+        .find('/')
+        .map_or(specifier.len(), |index| scope_end + 1 + index)
+    })
+  } else {
+    Some(specifier.find('/').unwrap_or(specifier.len()))
+  };
+  match name_length {
+    Some(length) if length > 0 && !specifier[..length].ends_with('/') => {
+      Ok((&specifier[..length], format!(".{}", &specifier[length..])))
+    }
+    _ => Err(Error::Validation(format!(
+      "[ERR_INVALID_MODULE_SPECIFIER] Invalid module specifier \"{}\".",
+      specifier
+    ))),
+  }
+}
+
+/// Prefix a path relative to the package root with `./`.
+fn relative_path(path: &str) -> String {
+  if path.starts_with("./") || path.starts_with("../") || path.starts_with('/') {
+    path.to_owned()
+  } else {
+    format!("./{}", path)
+  }
+}
+
+/// `PACKAGE_IMPORTS_EXPORTS_RESOLVE` in the specification.
+fn resolve_match_key(
+  match_key: &str,
+  match_obj: &IndexMap<String, PackageExportsTarget>,
+  is_imports: bool,
+  conditions: &[&str],
+) -> Result<Option<Resolved>> {
+  if !match_key.contains('*') {
+    if let Some(target) = match_obj.get(match_key) {
+      return Ok(resolve_target(target, None, is_imports, conditions)?);
+    }
+  }
+
+  let mut expansion_keys = match_obj
+    .keys()
+    .filter(|key| key.matches('*').count() == 1)
+    .collect::<Vec<_>>();
+  expansion_keys.sort_by(|a, b| pattern_key_compare(a, b));
+  for expansion_key in expansion_keys {
+    let (pattern_base, pattern_trailer) = expansion_key.split_once('*').unwrap();
+    if match_key.starts_with(pattern_base)
+      && match_key != pattern_base
+      && (pattern_trailer.is_empty()
+        || (match_key.ends_with(pattern_trailer) && match_key.len() >= expansion_key.len()))
+    {
+      let pattern_match = &match_key[pattern_base.len()..match_key.len() - pattern_trailer.len()];
+      return Ok(resolve_target(
+        &match_obj[expansion_key],
+        Some(pattern_match),
+        is_imports,
+        conditions,
+      )?);
+    }
+  }
+  Ok(None)
+}
+
+/// `PATTERN_KEY_COMPARE` in the specification, the more specific pattern comes first.
+fn pattern_key_compare(a: &str, b: &str) -> Ordering {
+  let base_length_a = a.find('*').map_or(0, |index| index + 1);
+  let base_length_b = b.find('*').map_or(0, |index| index + 1);
+  base_length_b
+    .cmp(&base_length_a)
+    .then_with(|| match (a.contains('*'), b.contains('*')) {
+      (false, _) => Ordering::Greater,
+      (_, false) => Ordering::Less,
+      _ => b.len().cmp(&a.len()),
+    })
+}
+
+/// Whether any segment of `path` is empty, `.`, `..` or `node_modules`.
+fn has_invalid_segment(path: &str) -> bool {
+  path.split(['/', '\\']).any(|segment| {
+    let segment = segment.to_lowercase().replace("%2e", ".");
+    segment.is_empty() || segment == "." || segment == ".." || segment == "node_modules"
+  })
+}
+
+/// `PACKAGE_TARGET_RESOLVE` in the specification.
+fn resolve_target(
+  target: &PackageExportsTarget,
+  pattern_match: Option<&str>,
+  is_imports: bool,
+  conditions: &[&str],
+) -> Result<Option<Resolved>, TargetError> {
+  match target {
+    PackageExportsTarget::Path(target) => {
+      let substitute = |target: &str| match pattern_match {
+        Some(pattern_match) => target.replace('*', pattern_match),
+        None => target.to_owned(),
+      };
+      if !target.starts_with("./") {
+        if !is_imports
+          || target.starts_with("../")
+          || target.starts_with('/')
+          || target.contains(':')
+        {
+          return Err(TargetError::InvalidPackageTarget(target.to_owned()));
+        }
+        // a bare specifier which refers to another package
+        return Ok(Some(Resolved::Target(substitute(target))));
+      }
+      if has_invalid_segment(&target[2..]) {
+        return Err(TargetError::InvalidPackageTarget(target.to_owned()));
+      }
+      if let Some(pattern_match) = pattern_match {
+        if has_invalid_segment(pattern_match) {
+          return Err(TargetError::InvalidModuleSpecifier(substitute(target)));
+        }
+      }
+      Ok(Some(Resolved::Target(substitute(target))))
+    }
+    PackageExportsTarget::Conditions(map) => {
+      for (condition, target) in map {
+        if condition == "default" || conditions.contains(&condition.as_str()) {
+          if let Some(resolved) = resolve_target(target, pattern_match, is_imports, conditions)? {
+            return Ok(Some(resolved));
+          }
+        }
+      }
+      Ok(None)
+    }
+    PackageExportsTarget::Fallbacks(targets) => {
+      let mut last = Ok(Some(Resolved::Null));
+      for target in targets {
+        match resolve_target(target, pattern_match, is_imports, conditions) {
+          Ok(None) => continue,
+          Ok(resolved) => return Ok(resolved),
+          Err(error @ TargetError::InvalidPackageTarget(_)) => last = Err(error),
+          Err(error) => return Err(error),
+        }
+      }
+      last
+    }
+    PackageExportsTarget::Null => Ok(Some(Resolved::Null)),
+  }
+}
+
+#[test]
+fn test_split_specifier() {
+  for (specifier, expected) in [
+    ("pkg", Some(("pkg", "."))),
+    ("pkg/sub/path", Some(("pkg", "./sub/path"))),
+    ("@scope/pkg", Some(("@scope/pkg", "."))),
+    ("@scope/pkg/sub", Some(("@scope/pkg", "./sub"))),
+    ("@scope", None),
+    ("/pkg", None),
+  ] {
+    assert_eq!(
+      split_specifier(specifier)
+        .ok()
+        .as_ref()
+        .map(|(name, subpath)| (*name, subpath.as_str())),
+      expected,
+      "split {}",
+      specifier
+    );
+  }
+}
+
+#[test]
+fn test_pattern_key_compare() {
+  let mut keys = vec!["./*", "./a/*.js", "./a/*", "./a/*/c.js"];
+  keys.sort_by(|a, b| pattern_key_compare(a, b));
+  assert_eq!(keys, vec!["./a/*/c.js", "./a/*.js", "./a/*", "./*"]);
+}
+
+#[test]
+fn test_resolve_exports() {
+  let json = serde_json::from_str::<PackageJson>(
+    r#"{
+      "name": "@scope/pkg",
+      "version": "1.0.0",
+      "exports": {
+        ".": {
+          "types": "./index.d.ts",
+          "node": { "import": "./node.mjs", "require": "./node.cjs" },
+          "default": "./index.js"
+        },
+        "./feature/*": "./src/feature/*.js",
+        "./feature/*.js": "./src/feature/*.js",
+        "./feature/internal/*": null,
+        "./fallback": ["invalid:target", "./fallback.js"],
+        "./conditional": { "browser": "./browser.js" },
+        "./escape/*": "./src/*.js",
+        "./invalid": "../outside.js"
+      }
+    }"#,
+  )
+  .unwrap();
+
+  for (specifier, conditions, expected) in [
+    ("@scope/pkg", vec!["node", "import"], Some("./node.mjs")),
+    ("@scope/pkg", vec!["node", "require"], Some("./node.cjs")),
+    (
+      "@scope/pkg",
+      vec!["types", "node", "import"],
+      Some("./index.d.ts"),
+    ),
+    ("@scope/pkg", vec!["browser"], Some("./index.js")),
+    ("@scope/pkg/feature/a", vec![], Some("./src/feature/a.js")),
+    (
+      "@scope/pkg/feature/a/b",
+      vec![],
+      Some("./src/feature/a/b.js"),
+    ),
+    (
+      "@scope/pkg/feature/a.js",
+      vec![],
+      Some("./src/feature/a.js"),
+    ),
+    ("@scope/pkg/feature/internal/a", vec![], None),
+    ("@scope/pkg/fallback", vec![], Some("./fallback.js")),
+    (
+      "@scope/pkg/conditional",
+      vec!["browser"],
+      Some("./browser.js"),
+    ),
+    ("@scope/pkg/conditional", vec!["node"], None),
+    ("@scope/pkg/escape/../secret", vec![], None),
+    ("@scope/pkg/invalid", vec![], None),
+    ("@scope/pkg/package.json", vec![], None),
+    ("other", vec![], None),
+  ] {
+    assert_eq!(
+      json.resolve(specifier, &conditions).ok().as_deref(),
+      expected,
+      "resolve {} with {:?}",
+      specifier,
+      conditions
+    );
+  }
+}
+
+#[test]
+fn test_resolve_sugar_exports() {
+  let json = serde_json::from_str::<PackageJson>(
+    r#"{ "name": "pkg", "version": "1.0.0", "exports": { "import": "./index.mjs", "default": "./index.cjs" } }"#,
+  )
+  .unwrap();
+  assert_eq!(json.resolve("pkg", &["import"]).unwrap(), "./index.mjs");
+  assert_eq!(json.resolve("pkg", &["require"]).unwrap(), "./index.cjs");
+  assert!(json.resolve("pkg/index.mjs", &["import"]).is_err());
+}
+
+#[test]
+fn test_resolve_legacy_main() {
+  let json = serde_json::from_str::<PackageJson>(
+    r#"{ "name": "pkg", "version": "1.0.0", "browser": "browser.js", "module": "./esm/index.js" }"#,
+  )
+  .unwrap();
+  assert_eq!(json.resolve("pkg", &["node"]).unwrap(), "./index.js");
+  assert_eq!(
+    json.resolve("pkg", &["browser", "import"]).unwrap(),
+    "./browser.js"
+  );
+  assert_eq!(json.resolve("pkg", &["import"]).unwrap(), "./esm/index.js");
+  assert_eq!(json.resolve("pkg/lib/a.js", &[]).unwrap(), "./lib/a.js");
+
+  let json = serde_json::from_str::<PackageJson>(
+    r#"{ "name": "pkg", "version": "1.0.0", "main": "lib/main.js" }"#,
+  )
+  .unwrap();
+  assert_eq!(json.resolve("pkg", &[]).unwrap(), "./lib/main.js");
+}
+
+#[test]
+fn test_resolve_imports() {
+  let json = serde_json::from_str::<PackageJson>(
+    r##"{
+      "name": "pkg",
+      "version": "1.0.0",
+      "imports": {
+        "#internal": "./src/internal.js",
+        "#utils/*": {
+          "node": { "import": "./src/utils/*.mjs", "require": "./src/utils/*.cjs" },
+          "default": "./src/utils/*.js"
+        },
+        "#utils/private/*": null,
+        "#dep": { "node": "dep-node-native", "default": "./dep-polyfill.js" },
+        "#invalid": "../outside.js"
+      }
+    }"##,
+  )
+  .unwrap();
+
+  for (specifier, conditions, expected) in [
+    ("#internal", vec![], Some("./src/internal.js")),
+    (
+      "#utils/a",
+      vec!["node", "import"],
+      Some("./src/utils/a.mjs"),
+    ),
+    (
+      "#utils/a/b",
+      vec!["node", "require"],
+      Some("./src/utils/a/b.cjs"),
+    ),
+    ("#utils/a", vec!["browser"], Some("./src/utils/a.js")),
+    ("#utils/private/a", vec![], None),
+    ("#dep", vec!["node"], Some("dep-node-native")),
+    ("#dep", vec!["browser"], Some("./dep-polyfill.js")),
+    ("#invalid", vec![], None),
+    ("#missing", vec![], None),
+    ("#", vec![], None),
+    ("#/a", vec![], None),
+    ("internal", vec![], None),
+  ] {
+    assert_eq!(
+      json.resolve_import(specifier, &conditions).ok().as_deref(),
+      expected,
+      "resolve {} with {:?}",
+      specifier,
+      conditions
+    );
+  }
+}
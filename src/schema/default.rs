@@ -1,12 +1,12 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 pub fn main() -> String {
   String::from("index.js")
 }
 
 /// see https://docs.npmjs.com/cli/v8/configuring-npm/package-json#default-values
-pub fn scripts() -> HashMap<String, String> {
-  HashMap::from([
+pub fn scripts() -> IndexMap<String, String> {
+  IndexMap::from([
     ("start".to_owned(), "node server.js".to_owned()),
     ("install".to_owned(), "node-gyp rebuild".to_owned()),
   ])
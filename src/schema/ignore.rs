@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
-pub fn ignore_scripts(data: &HashMap<String, String>) -> bool {
+pub fn ignore_scripts(data: &IndexMap<String, String>) -> bool {
   let default_value = super::default::scripts();
   data
     .keys()
@@ -1,8 +1,8 @@
 mod default;
 mod ignore;
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 /// A `package.json` is a JSON file that exists in the root of a JavaScript/Node.js project. It holds metadata relevant to the project and it's used for managing the project's dependencies, scripts, version and a whole lot more.
 ///
@@ -27,9 +27,12 @@ pub struct PackageJson {
   /// These are helpful for people who encounter issues with your package.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub bugs: Option<PackageBugs>,
-  /// The [license](https://spdx.org/licenses/) of the package.
+  /// The [license](https://spdx.org/licenses/) of the package, it should be an SPDX license expression. The legacy object form `{ "type": "MIT", "url": "..." }` is also supported.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub license: Option<String>,
+  pub license: Option<PackageLicense>,
+  /// The deprecated list of licenses, which is superseded by an SPDX license expression of the [license][PackageJson::license] field.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub licenses: Option<Vec<PackageLicenseRecord>>,
   /// The [author](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#people-fields-author-contributors) of the package.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub author: Option<PackagePeople>,
@@ -57,6 +60,14 @@ pub struct PackageJson {
   /// If your module is meant to be used client-side the [browser](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#browser) field should be used instead of the [main][PackageJson::main] field. This is helpful to hint users that it might rely on primitives that aren't available in Node.js modules. (e.g. window)
   #[serde(skip_serializing_if = "Option::is_none")]
   pub browser: Option<String>,
+  /// The [exports](https://nodejs.org/api/packages.html#exports) field defines the entry points of a package when it's imported by name, either loaded via a `node_modules` lookup or a self-reference to its own name. It takes precedence over the [main][PackageJson::main] field.
+  ///
+  /// It supports [subpath exports](https://nodejs.org/api/packages.html#subpath-exports), [subpath patterns](https://nodejs.org/api/packages.html#subpath-patterns) and [conditional exports](https://nodejs.org/api/packages.html#conditional-exports).
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub exports: Option<PackageExports>,
+  /// The [imports](https://nodejs.org/api/packages.html#imports) field defines private mappings which only apply to import specifiers from within the package itself. Every key starts with `#`, and supports the same conditions and subpath patterns as [exports][PackageJson::exports].
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub imports: Option<PackageImports>,
   /// A lot of packages have one or more executable files that they'd like to install into the PATH. npm makes this pretty easy (in fact, it uses this feature to install the "npm" executable.)
   ///
   /// To use this, supply a [bin](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#bin) field in your package.json which is a map of command name to local file name. When this package is installed globally, that file will be linked where global bins go so it is available to run by name. When this package is installed as a dependency in another package, the file will be linked where it will be available to that package either directly by npm exec or by name in other scripts when invoking them via npm run-script.
@@ -76,10 +87,10 @@ pub struct PackageJson {
   /// A dictionary containing script commands that are run at various times in the lifecycle of your package. The key is the lifecycle event, and the value is the command to run at that point.
   #[serde(default = "default::scripts")]
   #[serde(skip_serializing_if = "ignore::ignore_scripts")]
-  pub scripts: HashMap<String, String>,
+  pub scripts: IndexMap<String, String>,
   /// A [config](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#config) object can be used to set configuration parameters used in package scripts that persist across upgrades.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub config: Option<HashMap<String, serde_json::Value>>,
+  pub config: Option<IndexMap<String, serde_json::Value>>,
   /// [Dependencies](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#dependencies) are specified in a simple object that maps a package name to a version range. The version range is a string which has one or more space-separated descriptors. Dependencies can also be identified with a tarball or git URL.
   ///
   /// Please do not put test harnesses or transpilers or other "development" time tools in your dependencies object. See [devDependencies](PackageJson::dev_dependencies).
@@ -95,7 +106,7 @@ pub struct PackageJson {
   pub peer_dependencies: Option<PackageDependencies>,
   /// When a user installs your package, npm will emit warnings if packages specified in peerDependencies are not already installed. The [peerDependenciesMeta](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#peerdependenciesmeta) field serves to provide npm more information on how your [peer dependencies][PackageJson::peer_dependencies] are to be used. Specifically, it allows peer dependencies to be marked as optional.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub peer_dependencies_meta: Option<HashMap<String, HashMap<String, bool>>>,
+  pub peer_dependencies_meta: Option<IndexMap<String, IndexMap<String, bool>>>,
   /// An array of package names that will be bundled when publishing the package.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub bundled_dependencies: Option<Vec<String>>,
@@ -106,10 +117,10 @@ pub struct PackageJson {
   ///
   /// [Overrides](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#overrides) provide a way to replace a package in your dependency tree with another version, or another package entirely. These changes can be scoped as specific or as vague as desired.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub overrides: Option<HashMap<String, String>>,
+  pub overrides: Option<IndexMap<String, String>>,
   /// Specify which [engines](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#engines) your module will run on.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub engines: Option<HashMap<String, String>>,
+  pub engines: Option<IndexMap<String, String>>,
   /// Specify which [operating systems](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#os) your module will run on.
   #[serde(skip_serializing_if = "Option::is_none")]
   pub os: Option<Vec<String>>,
@@ -124,13 +135,15 @@ pub struct PackageJson {
   /// you can ensure that a given package is not tagged with "latest", published
   /// to the global public registry or that a scoped module is private by default.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub publish_config: Option<HashMap<String, String>>,
+  pub publish_config: Option<IndexMap<String, String>>,
   /// The optional [workspace](https://docs.npmjs.com/cli/v8/configuring-npm/package-json#workspaces)s
   /// field is an array of file patterns that describes locations within the local
   /// file system that the install client should look up to find each workspace
   /// that needs to be symlinked to the top level node_modules folder.
+  ///
+  /// Yarn classic also accepts the [object form](https://classic.yarnpkg.com/blog/2018/02/15/nohoist/) `{ "packages": [...], "nohoist": [...] }`.
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub workspaces: Option<Vec<String>>,
+  pub workspaces: Option<PackageWorkspaces>,
   /// When set to "module", the type field allows a package to specify all .js files within are ES modules. If the "type" field is omitted or set to "commonjs", all .js files are treated as CommonJS.
   #[serde(default = "default::r#type")]
   pub r#type: String,
@@ -143,11 +156,11 @@ pub struct PackageJson {
 
   /// Any unknown fields should be placed in `unknown` field.
   #[serde(flatten)]
-  pub unknowns: HashMap<String, serde_json::Value>,
+  pub unknowns: IndexMap<String, serde_json::Value>,
 }
 
 /// see [PackageJson::bugs](PackageJson::bugs)
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum PackageBugs {
   Url(String),
@@ -155,23 +168,46 @@ pub enum PackageBugs {
 }
 
 /// see [PackageJson::bugs](PackageJson::bugs)
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct PackageBugsRecord {
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub url: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub email: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// see [PackageJson::author](PackageJson::author)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum PackagePeople {
+  /// The shorthand string like `Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)`.
   Literal(String),
   Record(PackagePeopleRecord),
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+/// see [PackageJson::author](PackageJson::author)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct PackagePeopleRecord {
   pub name: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub email: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub url: Option<String>,
+}
+
+/// see [PackageJson::license](PackageJson::license)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PackageLicense {
+  Expression(String),
+  Record(PackageLicenseRecord),
+}
+
+/// see [PackageJson::license](PackageJson::license) and [PackageJson::licenses](PackageJson::licenses)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageLicenseRecord {
+  pub r#type: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub url: Option<String>,
 }
 
@@ -192,15 +228,15 @@ pub struct PackageFundingRecord {
 }
 
 /// see [PackageJson::bin](PackageJson::bin)
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum PackageBin {
   Literal(String),
-  Record(HashMap<String, String>),
+  Record(IndexMap<String, String>),
 }
 
 /// see [PackageJson::man](PackageJson::man)
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum PackageMan {
   Literal(String),
@@ -215,21 +251,105 @@ pub struct PackageDirectories {
 }
 
 /// see [PackageJson::repository](PackageJson::repository)
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum PackageRepository {
   Url(String),
   Record(PackageRepositoryRecord),
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct PackageRepositoryRecord {
   pub r#type: String,
   pub url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub directory: Option<String>,
 }
 
-pub type PackageDependencies = HashMap<String, String>;
+pub type PackageDependencies = IndexMap<String, String>;
+
+/// see [PackageJson::workspaces](PackageJson::workspaces)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PackageWorkspaces {
+  Slice(Vec<String>),
+  Record(PackageWorkspacesRecord),
+}
+
+/// The object form of [PackageJson::workspaces](PackageJson::workspaces) of Yarn classic.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageWorkspacesRecord {
+  /// The glob patterns of the workspaces.
+  #[serde(default)]
+  pub packages: Vec<String>,
+  /// The glob patterns of the dependencies which shouldn't be hoisted to the root `node_modules`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub nohoist: Option<Vec<String>>,
+}
+
+impl PackageWorkspaces {
+  /// The glob patterns of the workspaces in either form.
+  pub fn packages(&self) -> &[String] {
+    match self {
+      Self::Slice(packages) => packages,
+      Self::Record(record) => &record.packages,
+    }
+  }
+
+  /// The `nohoist` patterns, which are only available in the object form.
+  pub fn nohoist(&self) -> &[String] {
+    match self {
+      Self::Slice(_) => &[],
+      Self::Record(record) => record.nohoist.as_deref().unwrap_or_default(),
+    }
+  }
+}
+
+/// see [PackageJson::exports](PackageJson::exports)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged, try_from = "PackageExportsTarget")]
+pub enum PackageExports {
+  /// The shorthand of the package root export, e.g. `"exports": "./index.js"` or `"exports": { "import": "./index.mjs" }`, which is equivalent to `"exports": { ".": ... }`.
+  Main(PackageExportsTarget),
+  /// A map of subpaths to their targets, every key starts with `.`, e.g. `"exports": { ".": "./index.js", "./feature/*": "./src/feature/*.js" }`.
+  Subpaths(IndexMap<String, PackageExportsTarget>),
+}
+
+impl TryFrom<PackageExportsTarget> for PackageExports {
+  type Error = String;
+
+  fn try_from(target: PackageExportsTarget) -> Result<Self, Self::Error> {
+    match target {
+      PackageExportsTarget::Conditions(map) if map.keys().any(|key| key.starts_with('.')) => {
+        if map.keys().all(|key| key.starts_with('.')) {
+          Ok(Self::Subpaths(map))
+        } else {
+          Err(
+            "\"exports\" cannot contain some keys starting with '.' and some not, it should be either a subpaths object or a conditions object".to_owned(),
+          )
+        }
+      }
+      target => Ok(Self::Main(target)),
+    }
+  }
+}
+
+/// see [PackageJson::imports](PackageJson::imports)
+pub type PackageImports = IndexMap<String, PackageExportsTarget>;
+
+/// The target of a subpath in [PackageJson::exports](PackageJson::exports) or [PackageJson::imports](PackageJson::imports).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PackageExportsTarget {
+  /// `null`, which excludes the subpath from the exports.
+  Null,
+  /// A path relative to the package root, e.g. `"./dist/index.js"`. It may contain a `*` when its key is a subpath pattern.
+  Path(String),
+  /// A list of fallback targets, the first valid one is used.
+  Fallbacks(Vec<PackageExportsTarget>),
+  /// A map of conditions to their targets, e.g. `{ "types": "./index.d.ts", "import": "./index.mjs", "default": "./index.js" }`. The conditions are matched in the object order.
+  Conditions(IndexMap<String, PackageExportsTarget>),
+}
 
 #[test]
 fn test_spec_fields() {
@@ -254,7 +374,7 @@ fn test_spec_fields() {
   assert_eq!(json.dependencies, None);
   assert_eq!(
     json.dev_dependencies,
-    Some(HashMap::from([("typescript".to_owned(), "*".to_owned())]))
+    Some(IndexMap::from([("typescript".to_owned(), "*".to_owned())]))
   );
   assert_eq!(json.bundled_dependencies, None);
 
@@ -652,3 +772,130 @@ fn test_bin_and_man() {
     _ => panic!("Expected man to be a Slice"),
   }
 }
+
+#[test]
+fn test_exports() {
+  for (exports, expected) in [
+    (
+      serde_json::json!("./index.js"),
+      PackageExports::Main(PackageExportsTarget::Path("./index.js".to_owned())),
+    ),
+    (
+      serde_json::json!(["./index.js", "./fallback.js"]),
+      PackageExports::Main(PackageExportsTarget::Fallbacks(vec![
+        PackageExportsTarget::Path("./index.js".to_owned()),
+        PackageExportsTarget::Path("./fallback.js".to_owned()),
+      ])),
+    ),
+    (
+      serde_json::json!({ "import": "./index.mjs", "default": "./index.js" }),
+      PackageExports::Main(PackageExportsTarget::Conditions(IndexMap::from([
+        (
+          "import".to_owned(),
+          PackageExportsTarget::Path("./index.mjs".to_owned()),
+        ),
+        (
+          "default".to_owned(),
+          PackageExportsTarget::Path("./index.js".to_owned()),
+        ),
+      ]))),
+    ),
+    (
+      serde_json::json!({
+        ".": "./index.js",
+        "./feature/*": {
+          "types": "./types/feature/*.d.ts",
+          "node": { "import": "./feature/*.mjs", "require": "./feature/*.cjs" },
+          "default": "./feature/*.js"
+        },
+        "./internal/*": null
+      }),
+      PackageExports::Subpaths(IndexMap::from([
+        (
+          ".".to_owned(),
+          PackageExportsTarget::Path("./index.js".to_owned()),
+        ),
+        (
+          "./feature/*".to_owned(),
+          PackageExportsTarget::Conditions(IndexMap::from([
+            (
+              "types".to_owned(),
+              PackageExportsTarget::Path("./types/feature/*.d.ts".to_owned()),
+            ),
+            (
+              "node".to_owned(),
+              PackageExportsTarget::Conditions(IndexMap::from([
+                (
+                  "import".to_owned(),
+                  PackageExportsTarget::Path("./feature/*.mjs".to_owned()),
+                ),
+                (
+                  "require".to_owned(),
+                  PackageExportsTarget::Path("./feature/*.cjs".to_owned()),
+                ),
+              ])),
+            ),
+            (
+              "default".to_owned(),
+              PackageExportsTarget::Path("./feature/*.js".to_owned()),
+            ),
+          ])),
+        ),
+        ("./internal/*".to_owned(), PackageExportsTarget::Null),
+      ])),
+    ),
+  ] {
+    let package_json = serde_json::from_value::<PackageJson>(
+      serde_json::json!({ "name": "test", "version": "1.0.0", "exports": exports }),
+    )
+    .unwrap();
+    assert_eq!(package_json.exports.as_ref(), Some(&expected));
+    let serialized = serde_json::to_value(&package_json).unwrap();
+    assert_eq!(serialized["exports"], exports);
+    assert_eq!(
+      serde_json::to_string(&serialized["exports"]).unwrap(),
+      serde_json::to_string(&exports).unwrap()
+    );
+  }
+
+  assert!(serde_json::from_value::<PackageExports>(
+    serde_json::json!({ ".": "./index.js", "import": "./index.mjs" })
+  )
+  .is_err());
+}
+
+#[test]
+fn test_workspaces_serialization() {
+  for (workspaces, packages, nohoist) in [
+    (
+      serde_json::json!(["packages/*"]),
+      vec!["packages/*"],
+      vec![],
+    ),
+    (
+      serde_json::json!({ "packages": ["packages/*"], "nohoist": ["**/react-native"] }),
+      vec!["packages/*"],
+      vec!["**/react-native"],
+    ),
+    (
+      serde_json::json!({ "packages": ["packages/*"] }),
+      vec!["packages/*"],
+      vec![],
+    ),
+  ] {
+    let package_json = serde_json::from_value::<PackageJson>(serde_json::json!({
+      "name": "package-name",
+      "version": "1.0.0",
+      "workspaces": workspaces,
+    }))
+    .unwrap();
+    let parsed = package_json.workspaces.as_ref().unwrap();
+    assert_eq!(parsed.packages(), packages);
+    assert_eq!(parsed.nohoist(), nohoist);
+    assert_eq!(
+      serde_json::to_value(&package_json).unwrap()["workspaces"],
+      workspaces,
+      "the original form should be kept"
+    );
+  }
+}
@@ -0,0 +1,65 @@
+//! npm flavored [semantic versioning](https://semver.org/).
+
+use crate::error::Result;
+use crate::PackageJson;
+
+use self::version::{ReleaseType, Version};
+
+pub(crate) mod range;
+pub(crate) mod version;
+
+impl PackageJson {
+  /// Parse the [version][PackageJson::version] field. The field itself is kept as it is, so it's always written back unchanged.
+  /// ```
+  /// use package_json::PackageJson;
+  ///
+  /// let mut json = PackageJson::default();
+  /// json.version = "1.2.3-beta.1".to_string();
+  /// let version = json.parse_version().unwrap();
+  /// assert!(version.is_prerelease());
+  /// ```
+  pub fn parse_version(&self) -> Result<Version> {
+    Version::parse(&self.version)
+  }
+
+  /// Replace the [version][PackageJson::version] field with `version`.
+  pub fn set_version(&mut self, version: &Version) {
+    self.version = version.to_string();
+  }
+
+  /// Increase the [version][PackageJson::version] field by `release` as `npm version` does, and return the new version.
+  /// ```
+  /// use package_json::{PackageJson, ReleaseType};
+  ///
+  /// let mut json = PackageJson::default();
+  /// json.version = "1.2.3".to_string();
+  /// json.bump(ReleaseType::Premajor(Some("rc".to_string()))).unwrap();
+  /// assert_eq!(json.version, "2.0.0-rc.0");
+  /// ```
+  pub fn bump(&mut self, release: ReleaseType) -> Result<Version> {
+    let mut version = self.parse_version()?;
+    version.bump(&release)?;
+    self.set_version(&version);
+    Ok(version)
+  }
+}
+
+#[test]
+fn test_version_accessors() {
+  let mut json =
+    serde_json::from_str::<PackageJson>(r#"{ "name": "test", "version": "v1.0.0-rc.1+build" }"#)
+      .unwrap();
+  let version = json.parse_version().unwrap();
+  assert_eq!(version, Version::parse("1.0.0-rc.1").unwrap());
+  assert_eq!(version.build, vec!["build"]);
+  assert_eq!(
+    serde_json::to_value(&json).unwrap()["version"],
+    "v1.0.0-rc.1+build"
+  );
+
+  json.set_version(&Version::new(1, 0, 0));
+  assert_eq!(json.version, "1.0.0");
+
+  json.version = "latest".to_string();
+  assert!(json.parse_version().is_err());
+}
@@ -0,0 +1,522 @@
+use crate::error::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+use super::version::{is_identifier, parse_number, Identifier, Version};
+
+/// An npm [version range](https://github.com/npm/node-semver#ranges) like `^1.2.3`, `>=1.0.0 <2.0.0 || 3.x` or `1.0.0 - 1.5.0`, which is the value of a registry dependency in [PackageDependencies](crate::PackageDependencies).
+///
+/// A range is a union (`||`) of comparator sets, a version satisfies the range when it satisfies every comparator of any set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range {
+  sets: Vec<Vec<Comparator>>,
+}
+
+/// A primitive comparator of a [Range], e.g. `>=1.2.3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparator {
+  pub operator: Operator,
+  pub version: Version,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+  /// `=`
+  Eq,
+  /// `>`
+  Gt,
+  /// `>=`
+  Gte,
+  /// `<`
+  Lt,
+  /// `<=`
+  Lte,
+}
+
+impl Range {
+  /// Parse a range with the npm range grammar, including caret (`^`), tilde (`~`), X-ranges (`1.x`, `*`), hyphen ranges (`1.2.3 - 2.3.4`), comparator sets and `||` unions.
+  /// ```
+  /// use package_json::{Range, Version};
+  ///
+  /// let range = Range::parse("^1.2.3 || ~2.0").unwrap();
+  /// assert!(range.satisfies(&Version::parse("1.9.0").unwrap()));
+  /// assert!(range.satisfies(&Version::parse("2.0.5").unwrap()));
+  /// assert!(!range.satisfies(&Version::parse("2.1.0").unwrap()));
+  /// assert_eq!(range.to_string(), ">=1.2.3 <2.0.0-0 || >=2.0.0 <2.1.0-0");
+  /// ```
+  pub fn parse(range: &str) -> Result<Self> {
+    let sets = range
+      .split("||")
+      .map(|set| {
+        parse_set(set.trim()).ok_or_else(|| {
+          Error::Validation(format!(
+            "Invalid range \"{}\": invalid comparators \"{}\".",
+            range,
+            set.trim()
+          ))
+        })
+      })
+      .collect::<Result<Vec<_>>>()?;
+    Ok(Self { sets })
+  }
+
+  /// The comparator sets of the range, an empty set matches any version.
+  pub fn comparator_sets(&self) -> &[Vec<Comparator>] {
+    &self.sets
+  }
+
+  /// Whether `version` satisfies the range. As npm does, a prerelease version only satisfies a comparator set which contains a prerelease version of the same `[major, minor, patch]` tuple, e.g. `1.2.3-beta` satisfies `>=1.2.3-alpha` but doesn't satisfy `>=1.0.0`.
+  pub fn satisfies(&self, version: &Version) -> bool {
+    self.sets.iter().any(|set| {
+      set.iter().all(|comparator| comparator.matches(version))
+        && (!version.is_prerelease()
+          || set.iter().any(|comparator| {
+            comparator.version.is_prerelease()
+              && (
+                comparator.version.major,
+                comparator.version.minor,
+                comparator.version.patch,
+              ) == (version.major, version.minor, version.patch)
+          }))
+    })
+  }
+
+  /// The highest version in `versions` which satisfies the range.
+  /// ```
+  /// use package_json::{Range, Version};
+  ///
+  /// let versions = ["1.2.3", "1.4.0", "2.0.0"].map(|version| Version::parse(version).unwrap());
+  /// let range = Range::parse("^1.2.0").unwrap();
+  /// assert_eq!(range.max_satisfying(&versions), Some(&versions[1]));
+  /// ```
+  pub fn max_satisfying<'a, I>(&self, versions: I) -> Option<&'a Version>
+  where
+    I: IntoIterator<Item = &'a Version>,
+  {
+    versions
+      .into_iter()
+      .filter(|version| self.satisfies(version))
+      .max()
+  }
+
+  /// The lowest version in `versions` which satisfies the range.
+  pub fn min_satisfying<'a, I>(&self, versions: I) -> Option<&'a Version>
+  where
+    I: IntoIterator<Item = &'a Version>,
+  {
+    versions
+      .into_iter()
+      .filter(|version| self.satisfies(version))
+      .min()
+  }
+}
+
+impl Version {
+  /// Whether the version satisfies `range`, see [Range::satisfies].
+  pub fn satisfies(&self, range: &Range) -> bool {
+    range.satisfies(self)
+  }
+}
+
+impl Comparator {
+  fn new(operator: Operator, version: Version) -> Self {
+    Self { operator, version }
+  }
+
+  /// Whether `version` matches the comparator, regardless of prerelease tags.
+  pub fn matches(&self, version: &Version) -> bool {
+    match self.operator {
+      Operator::Eq => version == &self.version,
+      Operator::Gt => version > &self.version,
+      Operator::Gte => version >= &self.version,
+      Operator::Lt => version < &self.version,
+      Operator::Lte => version <= &self.version,
+    }
+  }
+}
+
+/// A version with optional missing or wildcard (`x`, `X`, `*`) parts, e.g. `1.2` or `1.x`.
+struct Partial {
+  major: Option<u64>,
+  minor: Option<u64>,
+  patch: Option<u64>,
+  prerelease: Vec<Identifier>,
+}
+
+impl Partial {
+  fn parse(partial: &str) -> Option<Self> {
+    let partial = partial.strip_prefix(['v', 'V']).unwrap_or(partial);
+    let (partial, build) = match partial.split_once('+') {
+      Some((partial, build)) => (partial, Some(build)),
+      None => (partial, None),
+    };
+    if build.is_some_and(|build| !build.split('.').all(is_identifier)) {
+      return None;
+    }
+    let (partial, prerelease) = match partial.split_once('-') {
+      Some((partial, prerelease)) => (
+        partial,
+        prerelease
+          .split('.')
+          .map(Identifier::parse)
+          .collect::<Option<Vec<_>>>()?,
+      ),
+      None => (partial, Vec::new()),
+    };
+
+    let mut numbers = Vec::new();
+    if !partial.is_empty() {
+      for number in partial.split('.') {
+        numbers.push(match number {
+          "x" | "X" | "*" => None,
+          number => Some(parse_number(number)?),
+        });
+      }
+    }
+    if numbers.len() > 3 {
+      return None;
+    }
+    numbers.resize(3, None);
+    let (major, minor, patch) = (numbers[0], numbers[1], numbers[2]);
+    // a wildcard can't be followed by a number or prerelease identifiers, e.g. `1.x.3`
+    if (major.is_none() && minor.is_some())
+      || (minor.is_none() && patch.is_some())
+      || (patch.is_none() && !prerelease.is_empty())
+    {
+      return None;
+    }
+    Some(Self {
+      major,
+      minor,
+      patch,
+      prerelease,
+    })
+  }
+
+  fn version(&self) -> Version {
+    Version {
+      prerelease: self.prerelease.clone(),
+      ..Version::new(
+        self.major.unwrap_or(0),
+        self.minor.unwrap_or(0),
+        self.patch.unwrap_or(0),
+      )
+    }
+  }
+}
+
+/// `<major>.<minor>.<patch>-0`, the lowest version of a `[major, minor, patch]` tuple, which is used as an exclusive upper bound.
+fn lowest(major: u64, minor: u64, patch: u64) -> Version {
+  Version {
+    prerelease: vec![Identifier::Numeric(0)],
+    ..Version::new(major, minor, patch)
+  }
+}
+
+fn parse_set(set: &str) -> Option<Vec<Comparator>> {
+  if let Some((from, to)) = set.split_once(" - ") {
+    return Some(hyphen(
+      &Partial::parse(from.trim())?,
+      &Partial::parse(to.trim())?,
+    ));
+  }
+
+  let mut comparators = Vec::new();
+  let mut operator = String::new();
+  for token in set.split_whitespace() {
+    // join the operator with the following version, e.g. `>= 1.2.3`
+    if token
+      .chars()
+      .all(|char| matches!(char, '<' | '>' | '=' | '~' | '^'))
+    {
+      operator.push_str(token);
+      continue;
+    }
+    let token = format!("{}{}", operator, token);
+    operator.clear();
+    comparators.extend(parse_comparator(&token)?);
+  }
+  if !operator.is_empty() {
+    return None;
+  }
+  Some(comparators)
+}
+
+fn parse_comparator(token: &str) -> Option<Vec<Comparator>> {
+  if let Some(partial) = token.strip_prefix("~>").or_else(|| token.strip_prefix('~')) {
+    return Some(tilde(&Partial::parse(partial.trim_start_matches('='))?));
+  }
+  if let Some(partial) = token.strip_prefix('^') {
+    return Some(caret(&Partial::parse(partial.trim_start_matches('='))?));
+  }
+  let (operator, partial) = [
+    (">=", Some(Operator::Gte)),
+    ("<=", Some(Operator::Lte)),
+    (">", Some(Operator::Gt)),
+    ("<", Some(Operator::Lt)),
+    ("=", None),
+  ]
+  .into_iter()
+  .find_map(|(prefix, operator)| {
+    token
+      .strip_prefix(prefix)
+      .map(|partial| (operator, partial))
+  })
+  .unwrap_or((None, token));
+  Some(x_range(
+    operator,
+    &Partial::parse(partial.trim_start_matches('='))?,
+  ))
+}
+
+/// `~1.2.3` := `>=1.2.3 <1.3.0-0`, `~1.2` := `>=1.2.0 <1.3.0-0`, `~1` := `>=1.0.0 <2.0.0-0`
+fn tilde(partial: &Partial) -> Vec<Comparator> {
+  let lower = Comparator::new(Operator::Gte, partial.version());
+  match (partial.major, partial.minor) {
+    (None, _) => Vec::new(),
+    (Some(major), None) => vec![
+      lower,
+      Comparator::new(Operator::Lt, lowest(major + 1, 0, 0)),
+    ],
+    (Some(major), Some(minor)) => vec![
+      lower,
+      Comparator::new(Operator::Lt, lowest(major, minor + 1, 0)),
+    ],
+  }
+}
+
+/// Allow changes which don't modify the left-most non-zero part, e.g. `^1.2.3` := `>=1.2.3 <2.0.0-0`, `^0.2.3` := `>=0.2.3 <0.3.0-0`, `^0.0.3` := `>=0.0.3 <0.0.4-0`
+fn caret(partial: &Partial) -> Vec<Comparator> {
+  let lower = Comparator::new(Operator::Gte, partial.version());
+  let upper = match (partial.major, partial.minor, partial.patch) {
+    (None, _, _) => return Vec::new(),
+    (Some(major), None, _) => lowest(major + 1, 0, 0),
+    (Some(0), Some(minor), None) => lowest(0, minor + 1, 0),
+    (Some(0), Some(0), Some(patch)) => lowest(0, 0, patch + 1),
+    (Some(0), Some(minor), Some(_)) => lowest(0, minor + 1, 0),
+    (Some(major), Some(_), _) => lowest(major + 1, 0, 0),
+  };
+  vec![lower, Comparator::new(Operator::Lt, upper)]
+}
+
+/// A primitive comparator which may contain wildcards, e.g. `1.x` := `>=1.0.0 <2.0.0-0`, `>1.2` := `>=1.3.0`, `<=1` := `<2.0.0-0`
+fn x_range(operator: Option<Operator>, partial: &Partial) -> Vec<Comparator> {
+  let (major, minor) = match (partial.major, partial.minor, partial.patch) {
+    (Some(_), Some(_), Some(_)) => {
+      return vec![Comparator::new(
+        operator.unwrap_or(Operator::Eq),
+        partial.version(),
+      )]
+    }
+    (None, _, _) => {
+      return match operator {
+        // nothing is greater or less than any version
+        Some(Operator::Gt | Operator::Lt) => {
+          vec![Comparator::new(Operator::Lt, lowest(0, 0, 0))]
+        }
+        _ => Vec::new(),
+      };
+    }
+    (Some(major), minor, _) => (major, minor),
+  };
+  match (operator, minor) {
+    (Some(Operator::Gt), None) => vec![Comparator::new(
+      Operator::Gte,
+      Version::new(major + 1, 0, 0),
+    )],
+    (Some(Operator::Gt), Some(minor)) => vec![Comparator::new(
+      Operator::Gte,
+      Version::new(major, minor + 1, 0),
+    )],
+    (Some(Operator::Gte), minor) => vec![Comparator::new(
+      Operator::Gte,
+      Version::new(major, minor.unwrap_or(0), 0),
+    )],
+    (Some(Operator::Lt), minor) => vec![Comparator::new(
+      Operator::Lt,
+      lowest(major, minor.unwrap_or(0), 0),
+    )],
+    (Some(Operator::Lte), None) => vec![Comparator::new(Operator::Lt, lowest(major + 1, 0, 0))],
+    (Some(Operator::Lte), Some(minor)) => {
+      vec![Comparator::new(Operator::Lt, lowest(major, minor + 1, 0))]
+    }
+    (_, None) => vec![
+      Comparator::new(Operator::Gte, Version::new(major, 0, 0)),
+      Comparator::new(Operator::Lt, lowest(major + 1, 0, 0)),
+    ],
+    (_, Some(minor)) => vec![
+      Comparator::new(Operator::Gte, Version::new(major, minor, 0)),
+      Comparator::new(Operator::Lt, lowest(major, minor + 1, 0)),
+    ],
+  }
+}
+
+/// `1.2 - 2.3.4` := `>=1.2.0 <=2.3.4`, `1.2.3 - 2.3` := `>=1.2.3 <2.4.0-0`
+fn hyphen(from: &Partial, to: &Partial) -> Vec<Comparator> {
+  let mut comparators = Vec::new();
+  if from.major.is_some() {
+    comparators.push(Comparator::new(Operator::Gte, from.version()));
+  }
+  match (to.major, to.minor, to.patch) {
+    (None, _, _) => {}
+    (Some(major), None, _) => {
+      comparators.push(Comparator::new(Operator::Lt, lowest(major + 1, 0, 0)))
+    }
+    (Some(major), Some(minor), None) => {
+      comparators.push(Comparator::new(Operator::Lt, lowest(major, minor + 1, 0)))
+    }
+    _ => comparators.push(Comparator::new(Operator::Lte, to.version())),
+  }
+  comparators
+}
+
+impl FromStr for Range {
+  type Err = Error;
+
+  fn from_str(range: &str) -> Result<Self> {
+    Self::parse(range)
+  }
+}
+
+impl fmt::Display for Operator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Eq => "",
+      Self::Gt => ">",
+      Self::Gte => ">=",
+      Self::Lt => "<",
+      Self::Lte => "<=",
+    })
+  }
+}
+
+impl fmt::Display for Comparator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", self.operator, self.version)
+  }
+}
+
+impl fmt::Display for Range {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let sets = self
+      .sets
+      .iter()
+      .map(|set| match set.is_empty() {
+        true => "*".to_owned(),
+        false => set
+          .iter()
+          .map(|comparator| comparator.to_string())
+          .collect::<Vec<_>>()
+          .join(" "),
+      })
+      .collect::<Vec<_>>();
+    f.write_str(&sets.join(" || "))
+  }
+}
+
+#[test]
+fn test_parse() {
+  for (range, expected) in [
+    ("", Some("*")),
+    ("*", Some("*")),
+    ("x", Some("*")),
+    ("1.2.3", Some("1.2.3")),
+    ("=v1.2.3", Some("1.2.3")),
+    (">= 1.2.3", Some(">=1.2.3")),
+    (">=1.2.3 <2", Some(">=1.2.3 <2.0.0-0")),
+    ("1.x", Some(">=1.0.0 <2.0.0-0")),
+    ("1.2.*", Some(">=1.2.0 <1.3.0-0")),
+    (">1", Some(">=2.0.0")),
+    (">1.2", Some(">=1.3.0")),
+    ("<1.2", Some("<1.2.0-0")),
+    ("<=1", Some("<2.0.0-0")),
+    (">*", Some("<0.0.0-0")),
+    ("~1", Some(">=1.0.0 <2.0.0-0")),
+    ("~1.2", Some(">=1.2.0 <1.3.0-0")),
+    ("~>1.2.3", Some(">=1.2.3 <1.3.0-0")),
+    ("~1.2.3-beta.2", Some(">=1.2.3-beta.2 <1.3.0-0")),
+    ("^1.2.3", Some(">=1.2.3 <2.0.0-0")),
+    ("^0.2.3", Some(">=0.2.3 <0.3.0-0")),
+    ("^0.0.3", Some(">=0.0.3 <0.0.4-0")),
+    ("^0.0", Some(">=0.0.0 <0.1.0-0")),
+    ("^1.x", Some(">=1.0.0 <2.0.0-0")),
+    ("^0.x", Some(">=0.0.0 <1.0.0-0")),
+    ("1.2 - 2.3.4", Some(">=1.2.0 <=2.3.4")),
+    ("1.2.3 - 2.3", Some(">=1.2.3 <2.4.0-0")),
+    ("1.2.3 - 2", Some(">=1.2.3 <3.0.0-0")),
+    ("1 || >=3 <4", Some(">=1.0.0 <2.0.0-0 || >=3.0.0 <4.0.0-0")),
+    ("latest", None),
+    ("1.x.3", None),
+    (">=", None),
+    ("^1.2.3.4", None),
+  ] {
+    assert_eq!(
+      Range::parse(range).ok().map(|range| range.to_string()),
+      expected.map(|expected| expected.to_owned()),
+      "parse {}",
+      range
+    );
+  }
+}
+
+#[test]
+fn test_satisfies() {
+  for (range, version, expected) in [
+    ("*", "1.2.3", true),
+    ("*", "1.2.3-beta", false),
+    ("1.2.3", "1.2.3+build", true),
+    ("^1.2.3", "1.9.9", true),
+    ("^1.2.3", "2.0.0", false),
+    ("^1.2.3", "2.0.0-alpha", false),
+    ("^1.2.3", "1.2.2", false),
+    ("^1.2.3-beta.2", "1.2.3-beta.4", true),
+    ("^1.2.3-beta.2", "1.2.4-beta.4", false),
+    ("~1.2.3", "1.2.9", true),
+    ("~1.2.3", "1.3.0", false),
+    (">=1.0.0 <2.0.0", "1.5.0", true),
+    (">=1.0.0 <2.0.0", "2.0.0", false),
+    ("1.0.0 - 2.0.0", "2.0.0", true),
+    ("<1.0.0 || >=2.0.0", "1.5.0", false),
+    ("<1.0.0 || >=2.0.0", "2.5.0", true),
+    (">1.2", "1.3.0", true),
+    (">1.2", "1.2.9", false),
+    ("<=1.2", "1.2.9", true),
+    ("<=1.2", "1.3.0", false),
+  ] {
+    assert_eq!(
+      Range::parse(range)
+        .unwrap()
+        .satisfies(&Version::parse(version).unwrap()),
+      expected,
+      "{} satisfies {}",
+      version,
+      range
+    );
+  }
+}
+
+#[test]
+fn test_max_satisfying() {
+  let versions = ["1.0.0", "1.2.0", "1.3.0-rc.1", "2.0.0", "2.1.0"]
+    .map(|version| Version::parse(version).unwrap());
+  for (range, max, min) in [
+    ("^1.0.0", Some("1.2.0"), Some("1.0.0")),
+    (">=1.3.0-rc.0 <2", Some("1.3.0-rc.1"), Some("1.3.0-rc.1")),
+    ("~2", Some("2.1.0"), Some("2.0.0")),
+    ("^3", None, None),
+  ] {
+    let range = Range::parse(range).unwrap();
+    assert_eq!(
+      range
+        .max_satisfying(&versions)
+        .map(|version| version.to_string()),
+      max.map(|max| max.to_owned())
+    );
+    assert_eq!(
+      range
+        .min_satisfying(&versions)
+        .map(|version| version.to_string()),
+      min.map(|min| min.to_owned())
+    );
+  }
+}
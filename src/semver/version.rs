@@ -0,0 +1,534 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// The largest integer which is safe in JavaScript, npm refuses the version numbers larger than it.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// A [semantic version](https://semver.org/) like `1.2.3-beta.1+build.5`, which is parsed the way npm does.
+///
+/// As npm does, the build metadata is ignored when versions are compared.
+#[derive(Debug, Clone)]
+pub struct Version {
+  pub major: u64,
+  pub minor: u64,
+  pub patch: u64,
+  /// The dot-separated prerelease identifiers, e.g. `["beta", 1]` of `1.2.3-beta.1`.
+  pub prerelease: Vec<Identifier>,
+  /// The dot-separated build metadata, e.g. `["build", "5"]` of `1.2.3+build.5`.
+  pub build: Vec<String>,
+}
+
+/// A prerelease identifier of a [Version].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+  Numeric(u64),
+  Alphanumeric(String),
+}
+
+impl Version {
+  /// Constructs a new `Version` without prerelease identifiers and build metadata.
+  pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+    Self {
+      major,
+      minor,
+      patch,
+      prerelease: Vec::new(),
+      build: Vec::new(),
+    }
+  }
+
+  /// Parse a version, a leading `v` or `=` is allowed as npm does, e.g. `v1.2.3`.
+  /// ```
+  /// use package_json::Version;
+  ///
+  /// let version = Version::parse("1.2.3-beta.1+build.5").unwrap();
+  /// assert_eq!((version.major, version.minor, version.patch), (1, 2, 3));
+  /// assert_eq!(version.to_string(), "1.2.3-beta.1+build.5");
+  /// assert!(Version::parse("1.2").is_err());
+  /// ```
+  pub fn parse(version: &str) -> Result<Self> {
+    let error =
+      |reason: &str| Error::Validation(format!("Invalid version \"{}\": {}.", version, reason));
+
+    let text = version.trim();
+    let text = text.strip_prefix('=').unwrap_or(text).trim_start();
+    let text = text.strip_prefix('v').unwrap_or(text);
+    let (text, build) = match text.split_once('+') {
+      Some((text, build)) => (text, Some(build)),
+      None => (text, None),
+    };
+    let (text, prerelease) = match text.split_once('-') {
+      Some((text, prerelease)) => (text, Some(prerelease)),
+      None => (text, None),
+    };
+
+    let numbers = text.split('.').collect::<Vec<_>>();
+    if numbers.len() != 3 {
+      return Err(error("expected <major>.<minor>.<patch>"));
+    }
+    let mut numbers =
+      numbers
+        .into_iter()
+        .zip(["major", "minor", "patch"])
+        .map(|(number, field)| {
+          parse_number(number)
+            .ok_or_else(|| error(&format!("invalid {} version \"{}\"", field, number)))
+        });
+    let major = numbers.next().unwrap()?;
+    let minor = numbers.next().unwrap()?;
+    let patch = numbers.next().unwrap()?;
+
+    let prerelease = match prerelease {
+      Some(prerelease) => prerelease
+        .split('.')
+        .map(|identifier| {
+          Identifier::parse(identifier)
+            .ok_or_else(|| error(&format!("invalid prerelease identifier \"{}\"", identifier)))
+        })
+        .collect::<Result<Vec<_>>>()?,
+      None => Vec::new(),
+    };
+    let build = match build {
+      Some(build) => build
+        .split('.')
+        .map(|identifier| {
+          if is_identifier(identifier) {
+            Ok(identifier.to_owned())
+          } else {
+            Err(error(&format!("invalid build metadata \"{}\"", identifier)))
+          }
+        })
+        .collect::<Result<Vec<_>>>()?,
+      None => Vec::new(),
+    };
+
+    Ok(Self {
+      major,
+      minor,
+      patch,
+      prerelease,
+      build,
+    })
+  }
+
+  /// Whether it's a prerelease version, e.g. `1.0.0-alpha`.
+  pub fn is_prerelease(&self) -> bool {
+    !self.prerelease.is_empty()
+  }
+}
+
+/// The release types of [Version::bump], which mirror the ones of `npm version`. The prerelease types take an optional prerelease identifier like `--preid` of `npm version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseType {
+  /// `1.2.3` -> `2.0.0`, `2.0.0-rc.1` -> `2.0.0`
+  Major,
+  /// `1.2.3` -> `1.3.0`, `1.3.0-rc.1` -> `1.3.0`
+  Minor,
+  /// `1.2.3` -> `1.2.4`, `1.2.4-rc.1` -> `1.2.4`
+  Patch,
+  /// `1.2.3` -> `2.0.0-0`, or `2.0.0-<preid>.0` with a prerelease identifier.
+  Premajor(Option<String>),
+  /// `1.2.3` -> `1.3.0-0`, or `1.3.0-<preid>.0` with a prerelease identifier.
+  Preminor(Option<String>),
+  /// `1.2.3` -> `1.2.4-0`, or `1.2.4-<preid>.0` with a prerelease identifier.
+  Prepatch(Option<String>),
+  /// `1.2.3` -> `1.2.4-0`, `1.2.4-0` -> `1.2.4-1`, `1.2.4-alpha.0` -> `1.2.4-beta.0` with the `beta` prerelease identifier.
+  Prerelease(Option<String>),
+}
+
+impl Version {
+  /// Increase the version by `release` as `npm version` does, the build metadata is dropped.
+  /// ```
+  /// use package_json::{ReleaseType, Version};
+  ///
+  /// let mut version = Version::parse("1.2.3").unwrap();
+  /// version.bump(&ReleaseType::Preminor(Some("beta".to_string()))).unwrap();
+  /// assert_eq!(version.to_string(), "1.3.0-beta.0");
+  /// version.bump(&ReleaseType::Prerelease(None)).unwrap();
+  /// assert_eq!(version.to_string(), "1.3.0-beta.1");
+  /// version.bump(&ReleaseType::Minor).unwrap();
+  /// assert_eq!(version.to_string(), "1.3.0");
+  /// ```
+  pub fn bump(&mut self, release: &ReleaseType) -> Result<()> {
+    // bump a copy, so that an invalid prerelease identifier leaves the version untouched.
+    let mut version = self.clone();
+    version.bump_in_place(release)?;
+    *self = version;
+    Ok(())
+  }
+
+  fn bump_in_place(&mut self, release: &ReleaseType) -> Result<()> {
+    match release {
+      ReleaseType::Major => {
+        if self.minor != 0 || self.patch != 0 || self.prerelease.is_empty() {
+          self.major += 1;
+        }
+        self.minor = 0;
+        self.patch = 0;
+        self.prerelease.clear();
+      }
+      ReleaseType::Minor => {
+        if self.patch != 0 || self.prerelease.is_empty() {
+          self.minor += 1;
+        }
+        self.patch = 0;
+        self.prerelease.clear();
+      }
+      ReleaseType::Patch => {
+        if self.prerelease.is_empty() {
+          self.patch += 1;
+        }
+        self.prerelease.clear();
+      }
+      ReleaseType::Premajor(preid) => {
+        self.major += 1;
+        self.minor = 0;
+        self.patch = 0;
+        self.prerelease.clear();
+        self.bump_prerelease(preid.as_deref())?;
+      }
+      ReleaseType::Preminor(preid) => {
+        self.minor += 1;
+        self.patch = 0;
+        self.prerelease.clear();
+        self.bump_prerelease(preid.as_deref())?;
+      }
+      ReleaseType::Prepatch(preid) => {
+        self.patch += 1;
+        self.prerelease.clear();
+        self.bump_prerelease(preid.as_deref())?;
+      }
+      ReleaseType::Prerelease(preid) => {
+        if self.prerelease.is_empty() {
+          self.patch += 1;
+        }
+        self.bump_prerelease(preid.as_deref())?;
+      }
+    }
+    self.build.clear();
+    Ok(())
+  }
+
+  fn bump_prerelease(&mut self, preid: Option<&str>) -> Result<()> {
+    match self
+      .prerelease
+      .iter_mut()
+      .rev()
+      .find_map(|identifier| match identifier {
+        Identifier::Numeric(number) => Some(number),
+        Identifier::Alphanumeric(_) => None,
+      }) {
+      Some(number) => *number += 1,
+      None => self.prerelease.push(Identifier::Numeric(0)),
+    }
+
+    if let Some(preid) = preid {
+      // a numeric identifier is accepted as well, e.g. `1.2.4-1.0` with `1`.
+      let Some(preid) = Identifier::parse(preid) else {
+        return Err(Error::Validation(format!(
+          "Invalid prerelease identifier \"{}\".",
+          preid
+        )));
+      };
+      let keep = matches!(
+        self.prerelease.as_slice(),
+        [current, Identifier::Numeric(_), ..] if *current == preid
+      );
+      if !keep {
+        self.prerelease = vec![preid, Identifier::Numeric(0)];
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Identifier {
+  pub(crate) fn parse(identifier: &str) -> Option<Self> {
+    if !is_identifier(identifier) {
+      return None;
+    }
+    if identifier.bytes().all(|byte| byte.is_ascii_digit()) {
+      parse_number(identifier).map(Self::Numeric)
+    } else {
+      Some(Self::Alphanumeric(identifier.to_owned()))
+    }
+  }
+}
+
+/// Parse a numeric identifier, which has no leading zeros and is safe in JavaScript.
+pub(crate) fn parse_number(number: &str) -> Option<u64> {
+  if number.is_empty()
+    || !number.bytes().all(|byte| byte.is_ascii_digit())
+    || (number.len() > 1 && number.starts_with('0'))
+  {
+    return None;
+  }
+  number
+    .parse::<u64>()
+    .ok()
+    .filter(|number| *number <= MAX_SAFE_INTEGER)
+}
+
+pub(crate) fn is_identifier(identifier: &str) -> bool {
+  !identifier.is_empty()
+    && identifier
+      .bytes()
+      .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+}
+
+impl FromStr for Version {
+  type Err = Error;
+
+  fn from_str(version: &str) -> Result<Self> {
+    Self::parse(version)
+  }
+}
+
+impl fmt::Display for Identifier {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Numeric(number) => write!(f, "{}", number),
+      Self::Alphanumeric(identifier) => f.write_str(identifier),
+    }
+  }
+}
+
+impl fmt::Display for Version {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+    if !self.prerelease.is_empty() {
+      let prerelease = self
+        .prerelease
+        .iter()
+        .map(|identifier| identifier.to_string())
+        .collect::<Vec<_>>();
+      write!(f, "-{}", prerelease.join("."))?;
+    }
+    if !self.build.is_empty() {
+      write!(f, "+{}", self.build.join("."))?;
+    }
+    Ok(())
+  }
+}
+
+impl Ord for Identifier {
+  fn cmp(&self, other: &Self) -> Ordering {
+    match (self, other) {
+      (Self::Numeric(a), Self::Numeric(b)) => a.cmp(b),
+      (Self::Numeric(_), Self::Alphanumeric(_)) => Ordering::Less,
+      (Self::Alphanumeric(_), Self::Numeric(_)) => Ordering::Greater,
+      (Self::Alphanumeric(a), Self::Alphanumeric(b)) => a.cmp(b),
+    }
+  }
+}
+
+impl PartialOrd for Identifier {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Version {
+  fn cmp(&self, other: &Self) -> Ordering {
+    (self.major, self.minor, self.patch)
+      .cmp(&(other.major, other.minor, other.patch))
+      .then_with(|| {
+        // a prerelease version has a lower precedence than the associated normal version
+        match (self.prerelease.is_empty(), other.prerelease.is_empty()) {
+          (true, true) => Ordering::Equal,
+          (true, false) => Ordering::Greater,
+          (false, true) => Ordering::Less,
+          (false, false) => self.prerelease.cmp(&other.prerelease),
+        }
+      })
+  }
+}
+
+impl PartialOrd for Version {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for Version {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    (self.major, self.minor, self.patch, &self.prerelease).hash(state);
+  }
+}
+
+impl Serialize for Version {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Version {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let version = String::deserialize(deserializer)?;
+    Self::parse(&version).map_err(serde::de::Error::custom)
+  }
+}
+
+#[test]
+fn test_parse() {
+  for (version, expected) in [
+    ("1.2.3", Some("1.2.3")),
+    ("v1.2.3", Some("1.2.3")),
+    ("=1.2.3", Some("1.2.3")),
+    (" 1.2.3 ", Some("1.2.3")),
+    ("1.2.3-alpha", Some("1.2.3-alpha")),
+    ("1.2.3-alpha.1.x-y", Some("1.2.3-alpha.1.x-y")),
+    ("1.2.3+build.001", Some("1.2.3+build.001")),
+    (
+      "1.2.3-rc.0+exp.sha.5114f85",
+      Some("1.2.3-rc.0+exp.sha.5114f85"),
+    ),
+    ("1.2.3-0a", Some("1.2.3-0a")),
+    ("1.2", None),
+    ("1.2.3.4", None),
+    ("01.2.3", None),
+    ("1.2.3-01", None),
+    ("1.2.3-", None),
+    ("1.2.3-a..b", None),
+    ("1.2.3+", None),
+    ("1.2.3-a_b", None),
+    ("a.b.c", None),
+    ("9007199254740992.0.0", None),
+    ("", None),
+  ] {
+    assert_eq!(
+      Version::parse(version)
+        .ok()
+        .map(|version| version.to_string()),
+      expected.map(|expected| expected.to_owned()),
+      "parse {}",
+      version
+    );
+  }
+}
+
+#[test]
+fn test_ordering() {
+  let versions = [
+    "1.0.0-alpha",
+    "1.0.0-alpha.1",
+    "1.0.0-alpha.beta",
+    "1.0.0-beta",
+    "1.0.0-beta.2",
+    "1.0.0-beta.11",
+    "1.0.0-rc.1",
+    "1.0.0",
+    "1.0.1",
+    "1.1.0",
+    "2.0.0",
+  ]
+  .map(|version| Version::parse(version).unwrap());
+  for window in versions.windows(2) {
+    assert!(window[0] < window[1], "{} < {}", window[0], window[1]);
+  }
+  assert_eq!(
+    Version::parse("1.0.0+a").unwrap(),
+    Version::parse("1.0.0+b").unwrap()
+  );
+}
+
+#[test]
+fn test_bump() {
+  let preid = |preid: &str| Some(preid.to_owned());
+  for (version, release, expected) in [
+    ("1.2.3", ReleaseType::Major, "2.0.0"),
+    ("1.2.3+build", ReleaseType::Major, "2.0.0"),
+    ("2.0.0-rc.1", ReleaseType::Major, "2.0.0"),
+    ("2.1.0-rc.1", ReleaseType::Major, "3.0.0"),
+    ("1.2.3", ReleaseType::Minor, "1.3.0"),
+    ("1.3.0-rc.1", ReleaseType::Minor, "1.3.0"),
+    ("1.3.1-rc.1", ReleaseType::Minor, "1.4.0"),
+    ("1.2.3", ReleaseType::Patch, "1.2.4"),
+    ("1.2.4-rc.1", ReleaseType::Patch, "1.2.4"),
+    ("1.2.3", ReleaseType::Premajor(None), "2.0.0-0"),
+    (
+      "1.2.3-1",
+      ReleaseType::Premajor(preid("alpha")),
+      "2.0.0-alpha.0",
+    ),
+    ("1.2.3", ReleaseType::Preminor(None), "1.3.0-0"),
+    (
+      "1.2.3",
+      ReleaseType::Preminor(preid("beta")),
+      "1.3.0-beta.0",
+    ),
+    ("1.2.3", ReleaseType::Prepatch(None), "1.2.4-0"),
+    (
+      "1.2.3-rc.1",
+      ReleaseType::Prepatch(preid("rc")),
+      "1.2.4-rc.0",
+    ),
+    ("1.2.3", ReleaseType::Prerelease(None), "1.2.4-0"),
+    ("1.2.4-0", ReleaseType::Prerelease(None), "1.2.4-1"),
+    (
+      "1.2.4-alpha",
+      ReleaseType::Prerelease(None),
+      "1.2.4-alpha.0",
+    ),
+    (
+      "1.2.4-alpha.1.b",
+      ReleaseType::Prerelease(None),
+      "1.2.4-alpha.2.b",
+    ),
+    (
+      "1.2.4-alpha.1",
+      ReleaseType::Prerelease(preid("alpha")),
+      "1.2.4-alpha.2",
+    ),
+    (
+      "1.2.4-alpha.1",
+      ReleaseType::Prerelease(preid("beta")),
+      "1.2.4-beta.0",
+    ),
+    (
+      "1.2.3",
+      ReleaseType::Prerelease(preid("beta")),
+      "1.2.4-beta.0",
+    ),
+    ("1.2.3", ReleaseType::Prerelease(preid("1")), "1.2.4-1.0"),
+    (
+      "1.2.4-1.0",
+      ReleaseType::Prerelease(preid("1")),
+      "1.2.4-1.1",
+    ),
+    (
+      "1.2.4-alpha.0",
+      ReleaseType::Prerelease(preid("1")),
+      "1.2.4-1.0",
+    ),
+    ("1.2.3", ReleaseType::Premajor(preid("7")), "2.0.0-7.0"),
+  ] {
+    let mut bumped = Version::parse(version).unwrap();
+    bumped.bump(&release).unwrap();
+    assert_eq!(
+      bumped.to_string(),
+      expected,
+      "bump {} by {:?}",
+      version,
+      release
+    );
+  }
+
+  let mut version = Version::parse("1.2.3").unwrap();
+  assert!(version.bump(&ReleaseType::Prerelease(preid("01"))).is_err());
+  assert!(version.bump(&ReleaseType::Premajor(preid("a.b"))).is_err());
+  // a failed bump leaves the version untouched.
+  assert_eq!(version.to_string(), "1.2.3");
+}
@@ -0,0 +1,524 @@
+//! The [workspaces](https://docs.npmjs.com/cli/v10/using-npm/workspaces) of a monorepo, which are the local packages listed by the `workspaces` field of the root `package.json`, or by the `packages` field of `pnpm-workspace.yaml`.
+
+use crate::error::{Error, Result};
+use crate::{PackageJson, PackageJsonManager, PackageWorkspaces, PACKAGE_JSON_FILENAME};
+use indexmap::IndexMap;
+use std::path::{Path, PathBuf};
+
+mod glob;
+pub(crate) mod graph;
+pub(crate) mod pnpm;
+
+use self::glob::Glob;
+use self::pnpm::{PnpmWorkspace, PNPM_WORKSPACE_FILENAME};
+
+/// The directories which are never searched for workspace members.
+const IGNORED_DIRS: [&str; 2] = ["node_modules", ".git"];
+
+/// A workspace root and its members.
+#[derive(Debug)]
+pub struct Workspace {
+  /// The directory of the root `package.json`.
+  pub root_dir: PathBuf,
+  /// The glob patterns of the members, a pattern with a leading `!` excludes the matched directories.
+  pub patterns: Vec<String>,
+  /// The `pnpm-workspace.yaml` next to the root `package.json`, if any.
+  pub pnpm: Option<PnpmWorkspace>,
+  /// The loaded members, in order of their relative paths.
+  pub members: Vec<WorkspaceMember>,
+  /// The problems found during discovery.
+  pub problems: Vec<WorkspaceProblem>,
+}
+
+/// A member package of a [Workspace].
+#[derive(Debug)]
+pub struct WorkspaceMember {
+  /// The `/`-separated path of the member directory relative to [Workspace::root_dir], e.g. `packages/a`.
+  pub relative_path: String,
+  /// The manager of the member `package.json`, which has been read.
+  pub manager: PackageJsonManager,
+}
+
+/// A problem found during workspace discovery, the problematic member isn't always fatal, so that it's reported instead of failing the whole discovery.
+#[derive(Debug)]
+pub enum WorkspaceProblem {
+  /// More than one member has the same name, all of them are kept in [Workspace::members].
+  DuplicateName {
+    name: String,
+    relative_paths: Vec<String>,
+  },
+  /// The `package.json` of a matched directory can't be read or parsed, it's left out of [Workspace::members]. It's also reported for a directory which can't be searched for members, e.g. without the permission.
+  Unreadable { relative_path: String, error: Error },
+}
+
+impl WorkspaceMember {
+  /// The directory of the member.
+  pub fn dir(&self) -> &Path {
+    self
+      .manager
+      .get_file_path()
+      .and_then(Path::parent)
+      .expect("a workspace member should have a file path")
+  }
+
+  /// The `package.json` of the member.
+  pub fn json(&self) -> &PackageJson {
+    self.manager.as_ref()
+  }
+}
+
+impl Workspace {
+  /// Discover the members of the workspace whose root `package.json` is managed by `root`. The root is read again, and its `workspaces` patterns are resolved as npm and yarn do. If there is a `pnpm-workspace.yaml` next to the root `package.json`, its `packages` patterns are used instead, as pnpm does.
+  ///
+  /// - A pattern matches the directories which contain a `package.json`, relative to the root directory, e.g. `packages/*`.
+  /// - `*`, `?`, `[...]`, `{a,b}` and `**` are supported, a wildcard never matches a hidden directory unless the pattern segment starts with `.`.
+  /// - A pattern with a leading `!` excludes the directories matched by it from the former patterns.
+  /// - Only the directories under the literal leading segments of the patterns are searched, e.g. `packages` of `packages/*`, and `node_modules` and `.git` are never searched. A directory which can't be searched is reported in [Workspace::problems].
+  /// ```
+  /// use package_json::{PackageJsonManager, Workspace};
+  ///
+  /// let mut manager = PackageJsonManager::new();
+  /// if manager.locate_closest().is_ok() {
+  ///   if let Ok(workspace) = Workspace::discover(&mut manager) {
+  ///     for member in &workspace.members {
+  ///       println!("{} at {}", member.json().name, member.relative_path);
+  ///     }
+  ///   }
+  /// }
+  /// ```
+  pub fn discover(root: &mut PackageJsonManager) -> Result<Self> {
+    let workspaces = root.read_ref()?.workspaces.clone();
+    let root_dir = root
+      .get_file_path()
+      .and_then(Path::parent)
+      .map(Path::to_path_buf)
+      .ok_or_else(|| Error::NotLocated {
+        filename: PACKAGE_JSON_FILENAME.to_owned(),
+        from: None,
+      })?;
+    let (patterns, pnpm) = member_patterns(&root_dir, workspaces.as_ref())?;
+    Self::discover_in(root_dir, patterns, pnpm)
+  }
+
+  /// Discover the members matched by `patterns` in `root_dir`, see [Workspace::discover].
+  fn discover_in(
+    root_dir: PathBuf,
+    patterns: Vec<String>,
+    pnpm: Option<PnpmWorkspace>,
+  ) -> Result<Self> {
+    let globs = MemberGlobs::new(&patterns);
+
+    let mut candidates = Vec::new();
+    let mut problems = Vec::new();
+    for (prefix, depth) in search_roots(&patterns) {
+      let dir = root_dir.join(&prefix);
+      if !dir.is_dir() {
+        continue;
+      }
+      if !prefix.is_empty() && dir.join(PACKAGE_JSON_FILENAME).is_file() {
+        candidates.push(prefix.clone());
+      }
+      collect_package_dirs(&root_dir, &prefix, depth, &mut candidates, &mut problems);
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    let mut members = Vec::new();
+    for relative_path in candidates {
+      if !globs.includes(&relative_path) {
+        continue;
+      }
+      let mut manager = PackageJsonManager::with_file_path(
+        root_dir.join(&relative_path).join(PACKAGE_JSON_FILENAME),
+      );
+      match manager.read_ref() {
+        Ok(_) => members.push(WorkspaceMember {
+          relative_path,
+          manager,
+        }),
+        Err(error) => problems.push(WorkspaceProblem::Unreadable {
+          relative_path,
+          error,
+        }),
+      }
+    }
+
+    let mut names = IndexMap::<&str, Vec<String>>::new();
+    for member in &members {
+      names
+        .entry(member.json().name.as_str())
+        .or_default()
+        .push(member.relative_path.clone());
+    }
+    problems.extend(
+      names
+        .into_iter()
+        .filter(|(_, relative_paths)| relative_paths.len() > 1)
+        .map(|(name, relative_paths)| WorkspaceProblem::DuplicateName {
+          name: name.to_owned(),
+          relative_paths,
+        }),
+    );
+
+    Ok(Self {
+      root_dir,
+      patterns,
+      pnpm,
+      members,
+      problems,
+    })
+  }
+
+  /// Resolve the range of `package` if its `spec` is a `catalog:` specifier, by the catalogs of `pnpm-workspace.yaml`. See [PnpmWorkspace::resolve_catalog].
+  pub fn resolve_catalog(&self, package: &str, spec: &str) -> Option<&str> {
+    self.pnpm.as_ref()?.resolve_catalog(package, spec)
+  }
+
+  /// Find a member by its package name.
+  pub fn member(&self, name: &str) -> Option<&WorkspaceMember> {
+    self
+      .members
+      .iter()
+      .find(|member| member.json().name == name)
+  }
+}
+
+/// The member patterns of the workspace root in `root_dir`, which come from `pnpm-workspace.yaml` if it exists, otherwise from the `workspaces` field of the root `package.json`.
+pub(crate) fn member_patterns(
+  root_dir: &Path,
+  workspaces: Option<&PackageWorkspaces>,
+) -> Result<(Vec<String>, Option<PnpmWorkspace>)> {
+  let pnpm_file_path = root_dir.join(PNPM_WORKSPACE_FILENAME);
+  if pnpm_file_path.is_file() {
+    let pnpm = PnpmWorkspace::read(&pnpm_file_path)?;
+    return Ok((pnpm.packages.clone(), Some(pnpm)));
+  }
+  let patterns = workspaces
+    .map(|workspaces| workspaces.packages().to_vec())
+    .unwrap_or_default();
+  Ok((patterns, None))
+}
+
+/// The compiled member patterns, the latter pattern takes precedence over the former ones.
+pub(crate) struct MemberGlobs(Vec<(bool, Glob)>);
+
+impl MemberGlobs {
+  pub(crate) fn new(patterns: &[String]) -> Self {
+    Self(
+      patterns
+        .iter()
+        .map(|pattern| match pattern.strip_prefix('!') {
+          Some(pattern) => (false, Glob::new(pattern)),
+          None => (true, Glob::new(pattern)),
+        })
+        .collect(),
+    )
+  }
+
+  /// Whether the member directory at the `/`-separated `relative_path` is included.
+  pub(crate) fn includes(&self, relative_path: &str) -> bool {
+    if relative_path
+      .split('/')
+      .any(|segment| IGNORED_DIRS.contains(&segment))
+    {
+      return false;
+    }
+    self.0.iter().fold(false, |included, (positive, glob)| {
+      match glob.matches(relative_path) {
+        true => *positive,
+        false => included,
+      }
+    })
+  }
+}
+
+/// The directories to search for the members matched by `patterns`, which are the literal leading segments of the including patterns, and how deep to search under them, `None` for any depth. A directory already covered by another one is left out.
+fn search_roots(patterns: &[String]) -> Vec<(String, Option<usize>)> {
+  let mut roots = patterns
+    .iter()
+    .filter(|pattern| !pattern.starts_with('!'))
+    .map(|pattern| {
+      let segments = pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>();
+      let literal = segments
+        .iter()
+        .take_while(|segment| !segment.contains(['*', '?', '[', '{', '\\']))
+        .count();
+      let rest = &segments[literal..];
+      // a brace group may contain `/`, so its depth is unknown.
+      let depth = match rest
+        .iter()
+        .any(|segment| segment.contains("**") || segment.contains('{'))
+      {
+        true => None,
+        false => Some(rest.len()),
+      };
+      (segments[..literal].to_vec(), depth)
+    })
+    .collect::<Vec<_>>();
+  // the roots which may cover others come first.
+  roots.sort_by_key(|(prefix, depth)| (prefix.len(), depth.map_or(0, |depth| usize::MAX - depth)));
+
+  let mut searched: Vec<(Vec<&str>, Option<usize>)> = Vec::new();
+  for (prefix, depth) in roots {
+    let covered = searched.iter().any(|(searched_prefix, searched_depth)| {
+      prefix.starts_with(searched_prefix)
+        && match (searched_depth, depth) {
+          (None, _) => true,
+          (Some(_), None) => false,
+          (Some(searched_depth), Some(depth)) => {
+            prefix.len() - searched_prefix.len() + depth <= *searched_depth
+          }
+        }
+    });
+    if !covered {
+      searched.push((prefix, depth));
+    }
+  }
+  searched
+    .into_iter()
+    .map(|(prefix, depth)| (prefix.join("/"), depth))
+    .collect()
+}
+
+/// Collect the `/`-separated relative paths of the descendant directories of `relative_dir` which contain a `package.json`, at most `depth` levels deep. `relative_dir` itself isn't collected. A directory which can't be read is reported as a problem.
+fn collect_package_dirs(
+  root_dir: &Path,
+  relative_dir: &str,
+  depth: Option<usize>,
+  paths: &mut Vec<String>,
+  problems: &mut Vec<WorkspaceProblem>,
+) {
+  if depth == Some(0) {
+    return;
+  }
+  let dir = root_dir.join(relative_dir);
+  let entries = match std::fs::read_dir(&dir) {
+    Ok(entries) => entries,
+    Err(source) => {
+      problems.push(WorkspaceProblem::Unreadable {
+        relative_path: relative_dir.to_owned(),
+        error: Error::Io { path: dir, source },
+      });
+      return;
+    }
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().into_owned();
+    // the symbolic links aren't followed, so that a link to an ancestor never loops.
+    let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+    if IGNORED_DIRS.contains(&name.as_str()) || !is_dir {
+      continue;
+    }
+    let relative_path = match relative_dir {
+      "" => name,
+      relative_dir => format!("{}/{}", relative_dir, name),
+    };
+    if entry.path().join(PACKAGE_JSON_FILENAME).is_file() {
+      paths.push(relative_path.clone());
+    }
+    collect_package_dirs(
+      root_dir,
+      &relative_path,
+      depth.map(|depth| depth - 1),
+      paths,
+      problems,
+    );
+  }
+}
+
+/// Write `contents` into the `package.json` in `relative_path` of `root`, the missing directories are created.
+#[cfg(test)]
+pub(crate) fn write_package(root: &Path, relative_path: &str, contents: &str) {
+  let dir = root.join(relative_path);
+  std::fs::create_dir_all(&dir).expect("create package dir failed!");
+  std::fs::write(dir.join(PACKAGE_JSON_FILENAME), contents).expect("write json failed");
+}
+
+#[test]
+fn test_discover() {
+  use std::env::current_dir;
+  use std::fs::{create_dir_all, write};
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let root = dir.path();
+  let manifest = |name: &str| format!(r#"{{ "name": "{}", "version": "1.0.0" }}"#, name);
+
+  write_package(
+    root,
+    "",
+    r#"{
+      "name": "root",
+      "version": "1.0.0",
+      "private": true,
+      "workspaces": ["packages/*", "apps/**", "!apps/legacy", "tools/cli"]
+    }"#,
+  );
+  write_package(root, "packages/a", &manifest("a"));
+  write_package(root, "packages/b", &manifest("b"));
+  write_package(root, "packages/.hidden", &manifest("hidden"));
+  write_package(root, "packages/a/node_modules/dep", &manifest("dep"));
+  write_package(root, "packages/broken", "{ \"name\": ");
+  write_package(root, "apps/web", &manifest("web"));
+  write_package(root, "apps/nested/mobile", &manifest("a"));
+  write_package(root, "apps/legacy", &manifest("legacy"));
+  write_package(root, "tools/cli", &manifest("cli"));
+  write_package(root, "tools/other", &manifest("other"));
+  create_dir_all(root.join("packages/empty")).unwrap();
+
+  let mut manager = PackageJsonManager::with_file_path(root.join(PACKAGE_JSON_FILENAME));
+  let workspace = Workspace::discover(&mut manager).unwrap();
+  assert_eq!(workspace.root_dir, root);
+  assert_eq!(
+    workspace
+      .members
+      .iter()
+      .map(|member| (member.relative_path.as_str(), member.json().name.as_str()))
+      .collect::<Vec<_>>(),
+    vec![
+      ("apps/nested/mobile", "a"),
+      ("apps/web", "web"),
+      ("packages/a", "a"),
+      ("packages/b", "b"),
+      ("tools/cli", "cli"),
+    ]
+  );
+  assert_eq!(
+    workspace.member("web").unwrap().dir(),
+    root.join("apps/web")
+  );
+  assert!(workspace.member("legacy").is_none());
+
+  assert_eq!(workspace.problems.len(), 2);
+  assert!(matches!(
+    &workspace.problems[0],
+    WorkspaceProblem::Unreadable { relative_path, error: Error::Parse(_) } if relative_path == "packages/broken"
+  ));
+  assert!(matches!(
+    &workspace.problems[1],
+    WorkspaceProblem::DuplicateName { name, relative_paths }
+      if name == "a" && relative_paths == &["apps/nested/mobile", "packages/a"]
+  ));
+
+  let mut manager =
+    PackageJsonManager::with_file_path(root.join("tools/cli").join(PACKAGE_JSON_FILENAME));
+  let workspace = Workspace::discover(&mut manager).unwrap();
+  assert!(workspace.members.is_empty() && workspace.problems.is_empty());
+
+  write_package(
+    root,
+    "",
+    r#"{
+      "name": "root",
+      "version": "1.0.0",
+      "workspaces": { "packages": ["tools/*"], "nohoist": ["**/react-native"] }
+    }"#,
+  );
+  let mut manager = PackageJsonManager::with_file_path(root.join(PACKAGE_JSON_FILENAME));
+  let workspace = Workspace::discover(&mut manager).unwrap();
+  assert_eq!(workspace.patterns, vec!["tools/*"]);
+  assert_eq!(workspace.members.len(), 2);
+
+  write(
+    root.join(PNPM_WORKSPACE_FILENAME),
+    "packages:\n  - packages/*\n  - '!packages/b'\ncatalog:\n  react: ^18.2.0\n",
+  )
+  .unwrap();
+  let workspace = Workspace::discover(&mut manager).unwrap();
+  assert_eq!(workspace.patterns, vec!["packages/*", "!packages/b"]);
+  assert_eq!(
+    workspace
+      .members
+      .iter()
+      .map(|member| member.relative_path.as_str())
+      .collect::<Vec<_>>(),
+    vec!["packages/a"]
+  );
+  assert_eq!(
+    workspace.resolve_catalog("react", "catalog:"),
+    Some("^18.2.0")
+  );
+
+  write(root.join(PNPM_WORKSPACE_FILENAME), "packages: 42\n").unwrap();
+  assert!(matches!(
+    Workspace::discover(&mut manager),
+    Err(Error::Parse(error)) if error.file_path == Some(root.join(PNPM_WORKSPACE_FILENAME))
+  ));
+
+  assert_eq!(
+    search_roots(
+      &[
+        "packages/*",
+        "./apps/**",
+        "!apps/legacy",
+        "tools/cli",
+        "apps/web/*",
+        "packages/*/*",
+        "{a,b}/*",
+      ]
+      .map(str::to_owned)
+    ),
+    vec![("".to_owned(), None),]
+  );
+  assert_eq!(
+    search_roots(
+      &[
+        "packages/*",
+        "./apps/**",
+        "!apps/legacy",
+        "tools/cli",
+        "apps/web/*",
+        "packages/*/*",
+      ]
+      .map(str::to_owned)
+    ),
+    vec![
+      ("apps".to_owned(), None),
+      ("packages".to_owned(), Some(2)),
+      ("tools/cli".to_owned(), Some(0)),
+    ]
+  );
+
+  #[cfg(unix)]
+  {
+    use std::fs::{set_permissions, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+
+    let locked = ["dist/locked", "packages/locked"];
+    for locked in locked {
+      create_dir_all(root.join(locked)).unwrap();
+      set_permissions(root.join(locked), Permissions::from_mode(0o000)).unwrap();
+    }
+    // the permissions don't restrict a privileged user.
+    if std::fs::read_dir(root.join(locked[0])).is_err() {
+      write(
+        root.join(PNPM_WORKSPACE_FILENAME),
+        "packages:\n  - packages/**\n",
+      )
+      .unwrap();
+      let workspace = Workspace::discover(&mut manager).unwrap();
+      // `dist` isn't searched since it's out of the patterns.
+      assert_eq!(
+        workspace
+          .problems
+          .iter()
+          .filter_map(|problem| match problem {
+            WorkspaceProblem::Unreadable {
+              relative_path,
+              error: Error::Io { .. },
+            } => Some(relative_path.as_str()),
+            _ => None,
+          })
+          .collect::<Vec<_>>(),
+        vec!["packages/locked"]
+      );
+      assert_eq!(workspace.member("a").unwrap().relative_path, "packages/a");
+    }
+    for locked in locked {
+      set_permissions(root.join(locked), Permissions::from_mode(0o755)).unwrap();
+    }
+  }
+}
@@ -0,0 +1,195 @@
+//! A glob matcher of `/`-separated relative paths, which supports `*`, `?`, `[...]`, `{a,b}` and `**`. As `node-glob` does, a wildcard never matches a leading `.` of a path segment.
+
+/// A compiled glob pattern.
+#[derive(Debug, Clone)]
+pub(crate) struct Glob {
+  /// The segments of every alternative after the braces are expanded.
+  alternatives: Vec<Vec<String>>,
+}
+
+impl Glob {
+  pub(crate) fn new(pattern: &str) -> Self {
+    let alternatives = expand_braces(pattern)
+      .into_iter()
+      .map(|pattern| {
+        let mut segments = Vec::new();
+        for segment in pattern.split('/') {
+          match segment {
+            "" | "." => {}
+            // consecutive globstars are equivalent to a single one.
+            "**" if segments.last().is_some_and(|last| last == "**") => {}
+            segment => segments.push(segment.to_owned()),
+          }
+        }
+        segments
+      })
+      .collect();
+    Self { alternatives }
+  }
+
+  /// Whether the relative `path` is matched, e.g. `packages/a`.
+  pub(crate) fn matches(&self, path: &str) -> bool {
+    let path = path
+      .split('/')
+      .filter(|segment| !segment.is_empty() && *segment != ".")
+      .collect::<Vec<_>>();
+    self
+      .alternatives
+      .iter()
+      .any(|segments| match_segments(segments, &path))
+  }
+}
+
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+  match pattern.split_first() {
+    None => path.is_empty(),
+    Some((globstar, rest)) if globstar == "**" => {
+      // a globstar matches zero or more segments, except the hidden ones.
+      (0..=path.len())
+        .take_while(|&skipped| skipped == 0 || !path[skipped - 1].starts_with('.'))
+        .any(|skipped| match_segments(rest, &path[skipped..]))
+    }
+    Some((segment, rest)) => match path.split_first() {
+      Some((name, path)) => match_segment(segment, name) && match_segments(rest, path),
+      None => false,
+    },
+  }
+}
+
+/// Match a single path segment `name` with `pattern`, which contains no `/`.
+fn match_segment(pattern: &str, name: &str) -> bool {
+  if name.starts_with('.') && !pattern.starts_with('.') {
+    return false;
+  }
+  let pattern = pattern.chars().collect::<Vec<_>>();
+  let name = name.chars().collect::<Vec<_>>();
+  match_chars(&pattern, &name)
+}
+
+fn match_chars(pattern: &[char], name: &[char]) -> bool {
+  match pattern.split_first() {
+    None => name.is_empty(),
+    Some(('*', rest)) => (0..=name.len()).any(|skipped| match_chars(rest, &name[skipped..])),
+    Some(('?', rest)) => !name.is_empty() && match_chars(rest, &name[1..]),
+    Some(('[', rest)) => match (name.split_first(), match_class(rest)) {
+      (Some((char, name)), Some((matches, rest))) => matches(*char) && match_chars(rest, name),
+      // an unclosed `[` is a literal.
+      (Some(('[', name)), None) => match_chars(rest, name),
+      _ => false,
+    },
+    Some(('\\', [escaped, rest @ ..])) => {
+      name.first() == Some(escaped) && match_chars(rest, &name[1..])
+    }
+    Some((char, rest)) => name.first() == Some(char) && match_chars(rest, &name[1..]),
+  }
+}
+
+/// Parse a character class after its `[`, return the predicate and the rest of the pattern after `]`.
+fn match_class(pattern: &[char]) -> Option<(impl Fn(char) -> bool + '_, &[char])> {
+  let (negated, body) = match pattern.first() {
+    Some('!' | '^') => (true, &pattern[1..]),
+    _ => (false, pattern),
+  };
+  // a `]` right after `[` is a literal.
+  let end = body.iter().skip(1).position(|char| *char == ']')? + 1;
+  let class = &body[..end];
+  let matches = move |char: char| {
+    let mut index = 0;
+    let mut matched = false;
+    while index < class.len() {
+      if index + 2 < class.len() && class[index + 1] == '-' {
+        matched |= (class[index]..=class[index + 2]).contains(&char);
+        index += 3;
+      } else {
+        matched |= class[index] == char;
+        index += 1;
+      }
+    }
+    matched != negated
+  };
+  Some((matches, &body[end + 1..]))
+}
+
+/// Expand the first brace group of `pattern` recursively, e.g. `{a,b}/*` into `a/*` and `b/*`.
+fn expand_braces(pattern: &str) -> Vec<String> {
+  let Some(open) = pattern.find('{') else {
+    return vec![pattern.to_owned()];
+  };
+  let mut depth = 0;
+  let mut alternatives = Vec::new();
+  let mut start = open + 1;
+  for (index, char) in pattern[open..]
+    .char_indices()
+    .map(|(index, char)| (index + open, char))
+  {
+    match char {
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          alternatives.push(&pattern[start..index]);
+          let (prefix, suffix) = (&pattern[..open], &pattern[index + 1..]);
+          // a group without `,` is a literal.
+          if alternatives.len() == 1 {
+            return expand_braces(suffix)
+              .into_iter()
+              .map(|suffix| format!("{}{{{}}}{}", prefix, alternatives[0], suffix))
+              .collect();
+          }
+          return alternatives
+            .into_iter()
+            .flat_map(|alternative| expand_braces(&format!("{}{}{}", prefix, alternative, suffix)))
+            .collect();
+        }
+      }
+      ',' if depth == 1 => {
+        alternatives.push(&pattern[start..index]);
+        start = index + 1;
+      }
+      _ => {}
+    }
+  }
+  // an unclosed `{` is a literal.
+  vec![pattern.to_owned()]
+}
+
+#[test]
+fn test_glob() {
+  for (pattern, path, expected) in [
+    ("packages/*", "packages/a", true),
+    ("packages/*", "packages/a/b", false),
+    ("packages/*", "packages", false),
+    ("./packages/*/", "packages/a", true),
+    ("packages/**", "packages/a/b", true),
+    ("packages/**", "packages", true),
+    ("**", "a/b/c", true),
+    ("**/b", "a/b", true),
+    ("**/b", "b", true),
+    ("a/**/d", "a/b/c/d", true),
+    ("a/**/d", "a/.b/c/d", false),
+    ("packages/*", "packages/.hidden", false),
+    ("packages/.*", "packages/.hidden", true),
+    ("packages/a?c", "packages/abc", true),
+    ("packages/a?c", "packages/ac", false),
+    ("packages/[ab]*", "packages/bar", true),
+    ("packages/[!ab]*", "packages/bar", false),
+    ("packages/[a-c]", "packages/b", true),
+    ("packages/[a-c]", "packages/d", false),
+    ("{apps,packages}/*", "apps/web", true),
+    ("{apps,packages}/*", "packages/a", true),
+    ("{apps,packages}/*", "tools/a", false),
+    ("packages/{a,b{1,2}}", "packages/b2", true),
+    ("packages/{a}", "packages/{a}", true),
+    ("packages/\\*", "packages/*", true),
+    ("packages/\\*", "packages/a", false),
+  ] {
+    assert_eq!(
+      Glob::new(pattern).matches(path),
+      expected,
+      "{:?} should{} match {:?}",
+      pattern,
+      if expected { "" } else { "n't" },
+      path
+    );
+  }
+}
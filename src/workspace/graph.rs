@@ -0,0 +1,387 @@
+//! The dependency graph between the members of a [Workspace].
+
+use crate::error::{Error, Result};
+use crate::workspace::Workspace;
+use crate::{DependencySpec, PackageDependencies, PackageJson, Version};
+use indexmap::{IndexMap, IndexSet};
+use std::collections::VecDeque;
+
+/// The dependency fields of `package.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+  Dependencies,
+  DevDependencies,
+  PeerDependencies,
+  OptionalDependencies,
+}
+
+impl DependencyKind {
+  /// All dependency fields.
+  pub const ALL: [Self; 4] = [
+    Self::Dependencies,
+    Self::DevDependencies,
+    Self::PeerDependencies,
+    Self::OptionalDependencies,
+  ];
+
+  /// The field name in `package.json`, e.g. `devDependencies`.
+  pub fn field(&self) -> &'static str {
+    match self {
+      Self::Dependencies => "dependencies",
+      Self::DevDependencies => "devDependencies",
+      Self::PeerDependencies => "peerDependencies",
+      Self::OptionalDependencies => "optionalDependencies",
+    }
+  }
+
+  /// The dependencies of this field in `json`.
+  pub fn of<'a>(&self, json: &'a PackageJson) -> Option<&'a PackageDependencies> {
+    match self {
+      Self::Dependencies => json.dependencies.as_ref(),
+      Self::DevDependencies => json.dev_dependencies.as_ref(),
+      Self::PeerDependencies => json.peer_dependencies.as_ref(),
+      Self::OptionalDependencies => json.optional_dependencies.as_ref(),
+    }
+  }
+}
+
+/// A dependency of a member on another member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyEdge {
+  /// The name of the dependent member.
+  pub from: String,
+  /// The name of the dependency member.
+  pub to: String,
+  pub kind: DependencyKind,
+  /// The dependency specifier, e.g. `workspace:^` or `^1.0.0`.
+  pub spec: String,
+}
+
+/// A directed graph of the members of a [Workspace], an edge points from a member to its dependency. See [Workspace::dependency_graph].
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+  /// The outgoing edges of every member, in order of the workspace members.
+  edges: IndexMap<String, Vec<DependencyEdge>>,
+}
+
+impl Workspace {
+  /// Build the dependency graph of the members from the given dependency fields. A dependency is an edge when its name is a member and either
+  ///
+  /// - it's specified by the `workspace:` protocol, or
+  /// - its range is satisfied by the version of the member, which is how npm and yarn link a member.
+  ///
+  /// A member depending on itself is ignored. When more than one member has the same name, the first one is used.
+  /// ```
+  /// use package_json::{DependencyKind, PackageJsonManager, Workspace};
+  ///
+  /// let mut manager = PackageJsonManager::new();
+  /// if manager.locate_workspace_root().is_ok() {
+  ///   if let Ok(workspace) = Workspace::discover(&mut manager) {
+  ///     let graph = workspace.dependency_graph(&DependencyKind::ALL);
+  ///     match graph.layers() {
+  ///       Ok(layers) => layers.iter().for_each(|layer| println!("{}", layer.join(", "))),
+  ///       Err(error) => println!("{}", error),
+  ///     }
+  ///   }
+  /// }
+  /// ```
+  pub fn dependency_graph(&self, kinds: &[DependencyKind]) -> DependencyGraph {
+    let mut versions = IndexMap::new();
+    for member in &self.members {
+      let json = member.json();
+      versions
+        .entry(json.name.as_str())
+        .or_insert_with(|| Version::parse(&json.version).ok());
+    }
+
+    let mut edges = IndexMap::<String, Vec<DependencyEdge>>::new();
+    for member in &self.members {
+      let json = member.json();
+      if edges.contains_key(&json.name) {
+        continue;
+      }
+      let dependencies = kinds
+        .iter()
+        .filter_map(|kind| Some((kind, kind.of(json)?)))
+        .flat_map(|(kind, dependencies)| {
+          dependencies
+            .iter()
+            .map(move |(name, spec)| (*kind, name, spec))
+        })
+        .filter(|(_, name, spec)| {
+          let Some(version) = versions.get(name.as_str()) else {
+            return false;
+          };
+          match DependencySpec::parse(spec) {
+            Ok(DependencySpec::Workspace(_)) => true,
+            Ok(spec @ DependencySpec::Range(_)) => version
+              .as_ref()
+              .zip(spec.range())
+              .is_some_and(|(version, range)| range.satisfies(version)),
+            _ => false,
+          }
+        })
+        .filter(|(_, name, _)| **name != json.name)
+        .map(|(kind, name, spec)| DependencyEdge {
+          from: json.name.clone(),
+          to: name.clone(),
+          kind,
+          spec: spec.clone(),
+        })
+        .collect();
+      edges.insert(json.name.clone(), dependencies);
+    }
+    DependencyGraph { edges }
+  }
+}
+
+impl DependencyGraph {
+  /// The names of the members, in order of the workspace members.
+  pub fn members(&self) -> Vec<&str> {
+    self.edges.keys().map(String::as_str).collect()
+  }
+
+  /// The edges from `member` to its dependencies.
+  pub fn dependencies(&self, member: &str) -> &[DependencyEdge] {
+    self.edges.get(member).map_or(&[], Vec::as_slice)
+  }
+
+  /// The names of the members which directly depend on `member`.
+  pub fn dependents(&self, member: &str) -> Vec<&str> {
+    self
+      .edges
+      .iter()
+      .filter(|(_, edges)| edges.iter().any(|edge| edge.to == member))
+      .map(|(name, _)| name.as_str())
+      .collect()
+  }
+
+  /// The names of the members which are affected by a change of `member`, that is, the members which depend on it directly or transitively. They are sorted by the distance to `member`, and `member` itself is excluded.
+  pub fn affected(&self, member: &str) -> Vec<&str> {
+    let mut affected = IndexSet::new();
+    let mut queue = VecDeque::from([member]);
+    while let Some(current) = queue.pop_front() {
+      for dependent in self.dependents(current) {
+        if dependent != member && affected.insert(dependent) {
+          queue.push_back(dependent);
+        }
+      }
+    }
+    affected.into_iter().collect()
+  }
+
+  /// Sort the members topologically into layers, a member only depends on the members of the former layers, so that the members of the same layer can be built in parallel. Fail if there is a cycle, see [DependencyGraph::cycles].
+  pub fn layers(&self) -> Result<Vec<Vec<&str>>> {
+    if let Some(cycle) = self.cycles().first() {
+      return Err(Error::Validation(format!(
+        "Couldn't sort the workspace members, there is a dependency cycle: {} -> {}.",
+        cycle.join(" -> "),
+        cycle[0]
+      )));
+    }
+    let mut layered = IndexSet::new();
+    let mut layers = Vec::new();
+    while layered.len() < self.edges.len() {
+      let layer = self
+        .edges
+        .iter()
+        .filter(|(name, edges)| {
+          !layered.contains(name.as_str())
+            && edges.iter().all(|edge| layered.contains(edge.to.as_str()))
+        })
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+      layered.extend(layer.iter().copied());
+      layers.push(layer);
+    }
+    Ok(layers)
+  }
+
+  /// Find the dependency cycles, one for each group of members which depend on each other. A cycle is the chain of members in order of dependency, e.g. `["a", "b", "c"]` means `a -> b -> c -> a`, which starts from the foremost member of the group.
+  pub fn cycles(&self) -> Vec<Vec<&str>> {
+    self
+      .strongly_connected_components()
+      .into_iter()
+      .filter(|component| component.len() > 1)
+      .filter_map(|component| self.shortest_cycle(component[0], &component))
+      .collect()
+  }
+
+  /// The strongly connected components by Tarjan's algorithm, each of them is sorted in order of the members, and they are sorted by their foremost members.
+  fn strongly_connected_components(&self) -> Vec<Vec<&str>> {
+    struct State<'a> {
+      graph: &'a DependencyGraph,
+      index: usize,
+      indices: IndexMap<&'a str, (usize, usize)>,
+      stack: Vec<&'a str>,
+      components: Vec<Vec<&'a str>>,
+    }
+
+    fn connect<'a>(state: &mut State<'a>, member: &'a str) {
+      state.indices.insert(member, (state.index, state.index));
+      state.index += 1;
+      state.stack.push(member);
+      for edge in state.graph.dependencies(member) {
+        let to = edge.to.as_str();
+        match state.indices.get(to) {
+          None => {
+            connect(state, to);
+            let low = state.indices[to].1.min(state.indices[member].1);
+            state.indices[member].1 = low;
+          }
+          Some(&(index, _)) if state.stack.contains(&to) => {
+            let low = index.min(state.indices[member].1);
+            state.indices[member].1 = low;
+          }
+          Some(_) => {}
+        }
+      }
+      let (index, low) = state.indices[member];
+      if index == low {
+        let position = state
+          .stack
+          .iter()
+          .rposition(|item| *item == member)
+          .unwrap();
+        state.components.push(state.stack.split_off(position));
+      }
+    }
+
+    let mut state = State {
+      graph: self,
+      index: 0,
+      indices: IndexMap::new(),
+      stack: Vec::new(),
+      components: Vec::new(),
+    };
+    for member in self.edges.keys() {
+      if !state.indices.contains_key(member.as_str()) {
+        connect(&mut state, member);
+      }
+    }
+    let order = |member: &str| self.edges.get_index_of(member).unwrap_or(usize::MAX);
+    let mut components = state.components;
+    for component in components.iter_mut() {
+      component.sort_by_key(|member| order(member));
+    }
+    components.sort_by_key(|component| order(component[0]));
+    components
+  }
+
+  /// The shortest chain from `start` back to itself within `component`.
+  fn shortest_cycle<'a>(&'a self, start: &'a str, component: &[&str]) -> Option<Vec<&'a str>> {
+    let mut previous = IndexMap::<&str, &str>::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+      for edge in self.dependencies(current) {
+        let to = edge.to.as_str();
+        if !component.contains(&to) {
+          continue;
+        }
+        if to == start {
+          let mut cycle = vec![current];
+          while let Some(member) = previous.get(cycle.last().unwrap()) {
+            cycle.push(member);
+          }
+          cycle.reverse();
+          return Some(cycle);
+        }
+        if !previous.contains_key(to) && to != start {
+          previous.insert(to, current);
+          queue.push_back(to);
+        }
+      }
+    }
+    None
+  }
+}
+
+#[test]
+fn test_dependency_graph() {
+  use crate::workspace::write_package;
+  use crate::{PackageJsonManager, PACKAGE_JSON_FILENAME};
+  use std::env::current_dir;
+  use tempfile::tempdir_in;
+
+  let dir = tempdir_in(current_dir().unwrap()).expect("create temp_dir failed!");
+  let root = dir.path();
+  write_package(
+    root,
+    "",
+    r#"{ "name": "root", "version": "1.0.0", "workspaces": ["packages/*"] }"#,
+  );
+  write_package(
+    root,
+    "packages/a",
+    r#"{ "name": "a", "version": "1.0.0", "dependencies": { "lodash": "^4.0.0" } }"#,
+  );
+  write_package(
+    root,
+    "packages/b",
+    r#"{ "name": "b", "version": "2.0.0", "dependencies": { "a": "workspace:*" } }"#,
+  );
+  write_package(
+    root,
+    "packages/c",
+    r#"{
+      "name": "c",
+      "version": "1.0.0",
+      "dependencies": { "a": "^1.0.0" },
+      "devDependencies": { "b": "^1.0.0", "c": "workspace:*" }
+    }"#,
+  );
+  write_package(
+    root,
+    "packages/d",
+    r#"{
+      "name": "d",
+      "version": "1.0.0",
+      "peerDependencies": { "b": "^2.0.0" },
+      "optionalDependencies": { "c": "workspace:^" }
+    }"#,
+  );
+
+  let mut manager = PackageJsonManager::with_file_path(root.join(PACKAGE_JSON_FILENAME));
+  let workspace = Workspace::discover(&mut manager).unwrap();
+  let graph = workspace.dependency_graph(&DependencyKind::ALL);
+  assert_eq!(graph.members(), vec!["a", "b", "c", "d"]);
+  // `b@2.0.0` doesn't satisfy `^1.0.0`, and a member depending on itself is ignored.
+  assert_eq!(
+    graph.dependencies("c"),
+    &[DependencyEdge {
+      from: "c".to_owned(),
+      to: "a".to_owned(),
+      kind: DependencyKind::Dependencies,
+      spec: "^1.0.0".to_owned(),
+    }]
+  );
+  assert_eq!(graph.dependents("a"), vec!["b", "c"]);
+  assert_eq!(graph.affected("a"), vec!["b", "c", "d"]);
+  assert_eq!(graph.affected("d"), Vec::<&str>::new());
+  assert!(graph.cycles().is_empty());
+  assert_eq!(
+    graph.layers().unwrap(),
+    vec![vec!["a"], vec!["b", "c"], vec!["d"]]
+  );
+
+  write_package(
+    root,
+    "packages/a",
+    r#"{ "name": "a", "version": "1.0.0", "devDependencies": { "d": "workspace:*" } }"#,
+  );
+  let workspace = Workspace::discover(&mut manager).unwrap();
+  let graph = workspace.dependency_graph(&DependencyKind::ALL);
+  assert_eq!(graph.cycles(), vec![vec!["a", "d", "b"]]);
+  assert_eq!(
+    graph.layers().unwrap_err().to_string(),
+    "Couldn't sort the workspace members, there is a dependency cycle: a -> d -> b -> a."
+  );
+  assert_eq!(graph.affected("b"), vec!["d", "a", "c"]);
+
+  let graph = workspace.dependency_graph(&[DependencyKind::Dependencies]);
+  assert!(graph.cycles().is_empty());
+  assert_eq!(
+    graph.layers().unwrap(),
+    vec![vec!["a", "d"], vec!["b", "c"]]
+  );
+}
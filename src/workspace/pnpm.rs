@@ -0,0 +1,126 @@
+//! The [`pnpm-workspace.yaml`](https://pnpm.io/pnpm-workspace_yaml) file, which declares the members and the [catalogs](https://pnpm.io/catalogs) of a pnpm workspace.
+
+use crate::error::{Error, Result};
+use crate::fs;
+use crate::{DependencySpec, ParseError};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const PNPM_WORKSPACE_FILENAME: &str = "pnpm-workspace.yaml";
+
+/// The name of the default catalog, which is referenced by both `catalog:` and `catalog:default`.
+const DEFAULT_CATALOG: &str = "default";
+
+/// The content of `pnpm-workspace.yaml`.
+/// ```
+/// use package_json::PnpmWorkspace;
+///
+/// let workspace = PnpmWorkspace::parse(r#"
+/// packages:
+///   - "packages/*"
+/// catalog:
+///   react: ^18.2.0
+/// catalogs:
+///   react17:
+///     react: ^17.0.2
+/// "#).unwrap();
+/// assert_eq!(workspace.packages, vec!["packages/*"]);
+/// assert_eq!(workspace.resolve_catalog("react", "catalog:"), Some("^18.2.0"));
+/// assert_eq!(workspace.resolve_catalog("react", "catalog:react17"), Some("^17.0.2"));
+/// assert_eq!(workspace.resolve_catalog("react", "^16.0.0"), None);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PnpmWorkspace {
+  /// The glob patterns of the members, a pattern with a leading `!` excludes the matched directories.
+  #[serde(default)]
+  pub packages: Vec<String>,
+  /// The default catalog, which maps a package name to its range.
+  #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+  pub catalog: IndexMap<String, String>,
+  /// The named catalogs.
+  #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+  pub catalogs: IndexMap<String, IndexMap<String, String>>,
+}
+
+impl PnpmWorkspace {
+  /// Parse the content of `pnpm-workspace.yaml`.
+  pub fn parse(contents: &str) -> Result<Self> {
+    // an empty file is a valid workspace without any member.
+    if contents.trim().is_empty() {
+      return Ok(Default::default());
+    }
+    serde_path_to_error::deserialize(serde_norway::Deserializer::from_str(contents))
+      .map_err(|error| Error::Parse(ParseError::from_yaml_error(contents, error)))
+  }
+
+  /// Read `pnpm-workspace.yaml` at `file_path`.
+  pub fn read<FilePath: AsRef<Path>>(file_path: FilePath) -> Result<Self> {
+    let contents = fs::read_file(&file_path)?;
+    Self::parse(&contents).map_err(|error| match error {
+      Error::Parse(error) => Error::Parse(error.with_file_path(file_path.as_ref())),
+      error => error,
+    })
+  }
+
+  /// Find a catalog by its name, an empty name or `default` is the default catalog, which is either `catalog` or `catalogs.default`.
+  pub fn catalog(&self, name: &str) -> Option<&IndexMap<String, String>> {
+    match name {
+      "" | DEFAULT_CATALOG if !self.catalog.is_empty() => Some(&self.catalog),
+      "" => self.catalogs.get(DEFAULT_CATALOG),
+      name => self.catalogs.get(name),
+    }
+  }
+
+  /// Resolve the range of `package` if its `spec` is a `catalog:` specifier. Return `None` if `spec` isn't a catalog specifier, or the catalog doesn't define `package`.
+  pub fn resolve_catalog(&self, package: &str, spec: &str) -> Option<&str> {
+    match DependencySpec::parse(spec) {
+      Ok(DependencySpec::Catalog(name)) => self.catalog(&name)?.get(package).map(String::as_str),
+      _ => None,
+    }
+  }
+}
+
+#[test]
+fn test_pnpm_workspace() {
+  let workspace = PnpmWorkspace::parse(
+    r#"
+packages:
+  - packages/*
+  - "!**/test/**"
+catalogs:
+  default:
+    react: ^18.2.0
+  legacy:
+    react: ^16.14.0
+"#,
+  )
+  .unwrap();
+  assert_eq!(workspace.packages, vec!["packages/*", "!**/test/**"]);
+  assert!(workspace.catalog.is_empty());
+  for (spec, expected) in [
+    ("catalog:", Some("^18.2.0")),
+    ("catalog:default", Some("^18.2.0")),
+    ("catalog:legacy", Some("^16.14.0")),
+    ("catalog:missing", None),
+    ("workspace:*", None),
+  ] {
+    assert_eq!(
+      workspace.resolve_catalog("react", spec),
+      expected,
+      "{}",
+      spec
+    );
+  }
+  assert_eq!(workspace.resolve_catalog("vue", "catalog:"), None);
+
+  assert_eq!(PnpmWorkspace::parse("").unwrap(), PnpmWorkspace::default());
+
+  let Err(Error::Parse(error)) =
+    PnpmWorkspace::parse("packages:\n  - a\ncatalog:\n  react: [18]\n")
+  else {
+    panic!("pnpm-workspace.yaml should be invalid");
+  };
+  assert_eq!(error.pointer, "/catalog/react");
+  assert_eq!((error.line, error.column), (4, 10));
+}